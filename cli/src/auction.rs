@@ -0,0 +1,455 @@
+use anchor_spl::associated_token::get_associated_token_address;
+use anchor_spl::token::TokenAccount;
+use anyhow::{anyhow, Result};
+use clap::Subcommand;
+use solana_sdk::{
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+};
+
+use bonding_client::auction::{self as client, Auction, DecayCurve, PricingMode, PurchaseReceipt, VestingSchedule};
+
+use crate::Config;
+
+#[derive(Subcommand)]
+pub enum Command {
+    /// Creates a descending auction, optionally funding its pool
+    InitAuction {
+        #[clap(long)]
+        payment_mint: Pubkey,
+        #[clap(long)]
+        sale_mint: Pubkey,
+        /// Unix timestamp
+        #[clap(long)]
+        start_timestamp: i64,
+        /// Unix timestamp [default: open-ended, requires --half-life]
+        #[clap(long)]
+        end_timestamp: Option<i64>,
+        /// Payment tokens per sale token
+        #[clap(long)]
+        ceil_price: u64,
+        /// Payment tokens per sale token
+        #[clap(long)]
+        floor_price: u64,
+        /// Halve the price every given seconds instead of descending linearly
+        #[clap(long, conflicts_with = "step_duration")]
+        half_life: Option<i64>,
+        /// Lower the linearly descending price once every given seconds
+        #[clap(long)]
+        step_duration: Option<i64>,
+        /// Slow the descent down as the pool sells out
+        #[clap(long)]
+        supply_aware: bool,
+        /// Seconds the price holds after a purchase
+        #[clap(long, default_value = "0")]
+        price_hold_duration: i64,
+        /// Raise of the price after a purchase, in basis points
+        #[clap(long, default_value = "0")]
+        price_bump_bps: u64,
+        /// Account receiving the payments [default: associated token account]
+        #[clap(long)]
+        payment_destination: Option<Pubkey>,
+        /// Charge every buyer the price of the final fill, refunding the difference after the auction
+        #[clap(long)]
+        uniform_price: bool,
+        /// Sale tokens to deposit into the pool
+        #[clap(long)]
+        deposit_amount: Option<u64>,
+        /// Source of the deposit [default: associated token account]
+        #[clap(long)]
+        source_account: Option<Pubkey>,
+    },
+    /// Sets the purchase limits of a pending auction, in sale tokens
+    UpdatePurchaseLimits {
+        #[clap(long)]
+        auction: Pubkey,
+        /// [default: unlimited]
+        #[clap(long)]
+        max_per_buyer: Option<u64>,
+        /// [default: unlimited]
+        #[clap(long)]
+        max_per_tx: Option<u64>,
+        #[clap(long, default_value = "0")]
+        min_purchase: u64,
+    },
+    /// Sets the vesting of the sale tokens purchased in a pending auction, or removes it
+    UpdateVesting {
+        #[clap(long)]
+        auction: Pubkey,
+        /// Unix timestamp the release starts at [default: no vesting]
+        #[clap(long, requires = "duration")]
+        start_timestamp: Option<i64>,
+        /// Seconds after the start before anything can be claimed
+        #[clap(long, default_value = "0")]
+        cliff_duration: i64,
+        /// Seconds after the start until everything is released
+        #[clap(long)]
+        duration: Option<i64>,
+    },
+    /// Sets the guardian who can cancel a pending auction, or removes it
+    UpdateGuardian {
+        #[clap(long)]
+        auction: Pubkey,
+        /// [default: no guardian]
+        #[clap(long)]
+        guardian: Option<Pubkey>,
+    },
+    /// Cancels a pending or in progress auction, as its authority or guardian
+    CancelAuction {
+        #[clap(long)]
+        auction: Pubkey,
+    },
+    /// Ends an auction in progress
+    EndAuction {
+        #[clap(long)]
+        auction: Pubkey,
+    },
+    /// Deposits sale tokens into the pool of an auction
+    Deposit {
+        #[clap(long)]
+        auction: Pubkey,
+        #[clap(long)]
+        amount: u64,
+        /// Source of the deposit [default: associated token account]
+        #[clap(long)]
+        source_account: Option<Pubkey>,
+    },
+    /// Returns the unsold tokens of an auction that is not in progress, keeping it open for the vesting claims
+    WithdrawUnsold {
+        #[clap(long)]
+        auction: Pubkey,
+        /// Account receiving the unsold tokens [default: associated token account]
+        #[clap(long)]
+        destination_account: Option<Pubkey>,
+    },
+    /// Closes an auction that is not in progress, returning the unsold tokens
+    CloseAuction {
+        #[clap(long)]
+        auction: Pubkey,
+        /// Account receiving the unsold tokens [default: associated token account]
+        #[clap(long)]
+        destination_account: Option<Pubkey>,
+    },
+    /// Buys sale tokens at the current price
+    Purchase {
+        #[clap(long)]
+        auction: Pubkey,
+        /// Sale tokens to buy
+        #[clap(long)]
+        amount: u64,
+        /// Tolerated price increase over the quote, in permille
+        #[clap(long, default_value = "10")]
+        slippage_tolerance: u64,
+        /// [default: associated token account]
+        #[clap(long)]
+        payment_source: Option<Pubkey>,
+        /// [default: associated token account]
+        #[clap(long)]
+        sale_destination: Option<Pubkey>,
+    },
+    /// Spends payment tokens on as many sale tokens as they buy at the current price
+    Spend {
+        #[clap(long)]
+        auction: Pubkey,
+        /// Payment tokens to spend
+        #[clap(long)]
+        amount: u64,
+        /// Tolerated decrease of the purchased amount under the quote, in permille
+        #[clap(long, default_value = "10")]
+        slippage_tolerance: u64,
+        /// [default: associated token account]
+        #[clap(long)]
+        payment_source: Option<Pubkey>,
+        /// [default: associated token account]
+        #[clap(long)]
+        sale_destination: Option<Pubkey>,
+    },
+    /// Claims the vested sale tokens purchased in an auction with a vesting
+    Claim {
+        #[clap(long)]
+        auction: Pubkey,
+        /// [default: associated token account]
+        #[clap(long)]
+        sale_destination: Option<Pubkey>,
+    },
+    /// Refunds a buyer of a uniform price auction the difference to the clearing price
+    ClaimRefund {
+        #[clap(long)]
+        auction: Pubkey,
+        /// [default: the keypair]
+        #[clap(long)]
+        buyer: Option<Pubkey>,
+        /// Payment token account of the buyer receiving the refund [default: associated token account]
+        #[clap(long)]
+        refund_account: Option<Pubkey>,
+    },
+    /// Sends the escrowed payments of a uniform price auction to its payment destination
+    WithdrawProceeds {
+        #[clap(long)]
+        auction: Pubkey,
+    },
+    /// Prints the sale statistics of an auction and the purchases of a buyer
+    Show {
+        #[clap(long)]
+        auction: Pubkey,
+        /// [default: the keypair]
+        #[clap(long)]
+        buyer: Option<Pubkey>,
+    },
+    /// Quotes the payment for buying sale tokens now
+    Quote {
+        #[clap(long)]
+        auction: Pubkey,
+        /// Sale tokens to buy
+        #[clap(long)]
+        amount: u64,
+    },
+}
+
+pub fn run(config: &Config, command: Command) -> Result<()> {
+    let payer = config.payer.pubkey();
+    match command {
+        Command::InitAuction {
+            payment_mint,
+            sale_mint,
+            start_timestamp,
+            end_timestamp,
+            ceil_price,
+            floor_price,
+            half_life,
+            step_duration,
+            supply_aware,
+            price_hold_duration,
+            price_bump_bps,
+            payment_destination,
+            uniform_price,
+            deposit_amount,
+            source_account,
+        } => {
+            let decay_curve = match (half_life, step_duration) {
+                (Some(half_life), _) => DecayCurve::Exponential { half_life },
+                (None, Some(step_duration)) => DecayCurve::Stepwise { step_duration },
+                (None, None) => DecayCurve::Linear,
+            };
+            let pricing_mode = if supply_aware {
+                PricingMode::TimeAndSupply
+            } else {
+                PricingMode::Time
+            };
+            let auction = Keypair::new();
+            let mut instructions = vec![client::initialize_auction(
+                &auction.pubkey(),
+                &payer,
+                &payment_mint,
+                &payment_destination.unwrap_or_else(|| get_associated_token_address(&payer, &payment_mint)),
+                &sale_mint,
+                start_timestamp,
+                end_timestamp,
+                ceil_price,
+                floor_price,
+                decay_curve,
+                pricing_mode,
+                price_hold_duration,
+                price_bump_bps,
+            )];
+            if uniform_price {
+                instructions.push(client::enable_uniform_price(&auction.pubkey(), &payer, &payment_mint));
+            }
+            if let Some(deposit_amount) = deposit_amount {
+                instructions.push(client::deposit_to_auction_pool(
+                    &auction.pubkey(),
+                    &payer,
+                    &sale_mint,
+                    &source_account.unwrap_or_else(|| get_associated_token_address(&payer, &sale_mint)),
+                    deposit_amount,
+                ));
+            }
+            config.send(&instructions, &[&auction])?;
+            println!("Auction: {}", auction.pubkey());
+        }
+        Command::Deposit {
+            auction,
+            amount,
+            source_account,
+        } => {
+            let auction_state: Auction = config.fetch(&auction)?;
+            let instruction = client::deposit_to_auction_pool(
+                &auction,
+                &payer,
+                &auction_state.sale_mint,
+                &source_account.unwrap_or_else(|| get_associated_token_address(&payer, &auction_state.sale_mint)),
+                amount,
+            );
+            config.send(&[instruction], &[])?;
+        }
+        Command::UpdatePurchaseLimits {
+            auction,
+            max_per_buyer,
+            max_per_tx,
+            min_purchase,
+        } => {
+            let instruction =
+                client::update_purchase_limits(&auction, &payer, max_per_buyer, max_per_tx, min_purchase);
+            config.send(&[instruction], &[])?;
+        }
+        Command::UpdateVesting {
+            auction,
+            start_timestamp,
+            cliff_duration,
+            duration,
+        } => {
+            let vesting = start_timestamp.zip(duration).map(|(start_timestamp, duration)| VestingSchedule {
+                start_timestamp,
+                cliff_duration,
+                duration,
+            });
+            config.send(&[client::update_vesting(&auction, &payer, vesting)], &[])?;
+        }
+        Command::UpdateGuardian { auction, guardian } => {
+            config.send(&[client::update_guardian(&auction, &payer, guardian)], &[])?;
+        }
+        Command::CancelAuction { auction } => {
+            config.send(&[client::cancel_auction(&auction, &payer)], &[])?;
+        }
+        Command::EndAuction { auction } => {
+            config.send(&[client::end_auction(&auction, &payer)], &[])?;
+        }
+        Command::WithdrawUnsold {
+            auction,
+            destination_account,
+        } => {
+            let auction_state: Auction = config.fetch(&auction)?;
+            let instruction = client::withdraw_unsold(
+                &auction,
+                &payer,
+                &auction_state.sale_mint,
+                &destination_account.unwrap_or_else(|| get_associated_token_address(&payer, &auction_state.sale_mint)),
+            );
+            config.send(&[instruction], &[])?;
+        }
+        Command::CloseAuction {
+            auction,
+            destination_account,
+        } => {
+            let auction_state: Auction = config.fetch(&auction)?;
+            let instruction = client::close_auction(
+                &auction,
+                &payer,
+                &auction_state.sale_mint,
+                &destination_account.unwrap_or_else(|| get_associated_token_address(&payer, &auction_state.sale_mint)),
+            );
+            config.send(&[instruction], &[])?;
+        }
+        Command::Purchase {
+            auction,
+            amount,
+            slippage_tolerance,
+            payment_source,
+            sale_destination,
+        } => {
+            let auction_state: Auction = config.fetch(&auction)?;
+            let expected_payment = quote(config, &auction_state, amount)?;
+            let instruction = client::purchase(
+                &auction,
+                &auction_state,
+                &payer,
+                &payment_source.unwrap_or_else(|| get_associated_token_address(&payer, &auction_state.payment_mint)),
+                &sale_destination.unwrap_or_else(|| get_associated_token_address(&payer, &auction_state.sale_mint)),
+                amount,
+                expected_payment,
+                slippage_tolerance,
+            );
+            config.send(&[instruction], &[])?;
+        }
+        Command::Spend {
+            auction,
+            amount,
+            slippage_tolerance,
+            payment_source,
+            sale_destination,
+        } => {
+            let auction_state: Auction = config.fetch(&auction)?;
+            let sale_decimals = config.mint_decimals(&auction_state.sale_mint)?;
+            let pool: TokenAccount = config.fetch(&auction_state.auction_pool)?;
+            let purchased_amount = config
+                .fetch_optional::<PurchaseReceipt>(&client::purchase_receipt_address(&auction, &payer).0)?
+                .map_or(0, |receipt| receipt.purchased_amount);
+            let expected_amount = client::purchase_amount(&auction_state, amount, sale_decimals, config.now()?)
+                .map_err(|err| anyhow!("failed to quote: {}", err))?
+                .min(auction_state.get_available_amount(pool.amount))
+                .min(auction_state.get_purchase_allowance(purchased_amount));
+            let min_purchase_amount =
+                (expected_amount as u128 * 1_000u128.saturating_sub(slippage_tolerance as u128) / 1_000) as u64;
+            let instruction = client::purchase_exact_payment(
+                &auction,
+                &auction_state,
+                &payer,
+                &payment_source.unwrap_or_else(|| get_associated_token_address(&payer, &auction_state.payment_mint)),
+                &sale_destination.unwrap_or_else(|| get_associated_token_address(&payer, &auction_state.sale_mint)),
+                amount,
+                min_purchase_amount,
+            );
+            config.send(&[instruction], &[])?;
+            println!("Expected sale amount: {}", expected_amount);
+        }
+        Command::Claim {
+            auction,
+            sale_destination,
+        } => {
+            let auction_state: Auction = config.fetch(&auction)?;
+            let instruction = client::claim(
+                &auction,
+                &auction_state,
+                &payer,
+                &sale_destination.unwrap_or_else(|| get_associated_token_address(&payer, &auction_state.sale_mint)),
+            );
+            config.send(&[instruction], &[])?;
+        }
+        Command::ClaimRefund {
+            auction,
+            buyer,
+            refund_account,
+        } => {
+            let auction_state: Auction = config.fetch(&auction)?;
+            let buyer = buyer.unwrap_or(payer);
+            let refund_account =
+                refund_account.unwrap_or_else(|| get_associated_token_address(&buyer, &auction_state.payment_mint));
+            config.send(&[client::claim_refund(&auction, &auction_state, &buyer, &refund_account)], &[])?;
+        }
+        Command::WithdrawProceeds { auction } => {
+            let auction_state: Auction = config.fetch(&auction)?;
+            config.send(&[client::withdraw_proceeds(&auction, &auction_state)], &[])?;
+        }
+        Command::Show { auction, buyer } => {
+            let auction_state: Auction = config.fetch(&auction)?;
+            println!("Sold: {} of {}", auction_state.total_sold, auction_state.total_deposited);
+            println!("Raised: {}", auction_state.total_raised);
+            println!("Purchases: {}", auction_state.purchase_count);
+            let buyer = buyer.unwrap_or(payer);
+            if let Some(receipt) =
+                config.fetch_optional::<PurchaseReceipt>(&client::purchase_receipt_address(&auction, &buyer).0)?
+            {
+                println!(
+                    "Buyer {}: bought {}, paid {}, claimed {}, last purchase {}{}",
+                    buyer,
+                    receipt.purchased_amount,
+                    receipt.paid_amount,
+                    receipt.claimed_amount,
+                    receipt.last_purchase_timestamp,
+                    if receipt.refunded { ", refunded" } else { "" },
+                );
+            }
+        }
+        Command::Quote { auction, amount } => {
+            let auction_state: Auction = config.fetch(&auction)?;
+            println!("Payment amount: {}", quote(config, &auction_state, amount)?);
+        }
+    }
+    Ok(())
+}
+
+fn quote(config: &Config, auction: &Auction, purchase_amount: u64) -> Result<u64> {
+    let sale_decimals = config.mint_decimals(&auction.sale_mint)?;
+    client::payment_amount(auction, purchase_amount, sale_decimals, config.now()?)
+        .map_err(|err| anyhow!("failed to quote: {}", err))
+}
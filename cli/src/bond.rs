@@ -0,0 +1,360 @@
+use anchor_spl::associated_token::get_associated_token_address;
+use anyhow::{anyhow, Result};
+use clap::{Args, Subcommand};
+use solana_sdk::{pubkey::Pubkey, signature::Signer};
+
+use bonding_client::bond::{
+    self as client, BondsInfo, DiscountSettings, MilestoneTable, ProjectBonds, ProjectInfo, VestingInfo, VestingSchedule,
+};
+
+use crate::Config;
+
+#[derive(Args)]
+pub struct VestingArgs {
+    /// Seconds between two releases
+    #[clap(long)]
+    pub release_interval: u64,
+    /// Release percent at each interval
+    #[clap(long)]
+    pub release_rate: u64,
+    /// Percent unlocked at bonding
+    #[clap(long, default_value = "0")]
+    pub instant_unlock: u64,
+    /// Percent unlocked at the end of the lock period
+    #[clap(long, default_value = "0")]
+    pub initial_unlock: u64,
+    /// Seconds before the release starts
+    #[clap(long, default_value = "0")]
+    pub lock_period: u64,
+    /// Seconds to release everything after the lock period
+    #[clap(long)]
+    pub vesting_period: u64,
+}
+
+impl From<&VestingArgs> for VestingSchedule {
+    fn from(args: &VestingArgs) -> Self {
+        VestingSchedule {
+            release_interval: args.release_interval,
+            release_rate: args.release_rate,
+            instant_unlock: args.instant_unlock,
+            initial_unlock: args.initial_unlock,
+            lock_period: args.lock_period,
+            vesting_period: args.vesting_period,
+        }
+    }
+}
+
+#[derive(Subcommand)]
+pub enum Command {
+    /// Creates a bonding project and deposits its tokens
+    InitProject {
+        #[clap(long)]
+        token_mint: Pubkey,
+        #[clap(long)]
+        lp_mint: Pubkey,
+        /// Project tokens to deposit
+        #[clap(long)]
+        amount: u64,
+        #[clap(long)]
+        price: u64,
+        /// Minimum discount in bips
+        #[clap(long, default_value = "0")]
+        min_discount: u64,
+        /// Maximum discount in bips
+        #[clap(long, default_value = "0")]
+        max_discount: u64,
+        /// 1: discount grows from min to max, 2: max to min
+        #[clap(long, default_value = "1")]
+        discount_mode: u64,
+        #[clap(flatten)]
+        vesting: VestingArgs,
+        /// Source of the project tokens [default: associated token account]
+        #[clap(long)]
+        token_account: Option<Pubkey>,
+        /// Account receiving the bonded lp tokens [default: associated token account]
+        #[clap(long)]
+        lp_token_account: Option<Pubkey>,
+    },
+    /// Sets the price of a project
+    UpdatePrice {
+        #[clap(long)]
+        token_mint: Pubkey,
+        #[clap(long)]
+        project_id: u64,
+        #[clap(long)]
+        new_price: u64,
+    },
+    /// Bonds lp tokens into a project
+    Bond {
+        #[clap(long)]
+        token_mint: Pubkey,
+        #[clap(long)]
+        project_id: u64,
+        #[clap(long)]
+        lp_amount: u64,
+        /// Source of the lp tokens [default: associated token account]
+        #[clap(long)]
+        lp_deposit_account: Option<Pubkey>,
+    },
+    /// Withdraws the vested tokens of one position, or of every position when no bond id is given
+    Withdraw {
+        #[clap(long)]
+        token_mint: Pubkey,
+        #[clap(long)]
+        project_id: u64,
+        #[clap(long)]
+        bond_id: Option<u64>,
+        /// Account receiving the project tokens [default: associated token account]
+        #[clap(long)]
+        receive_account: Option<Pubkey>,
+    },
+    /// Prints the state of a project
+    ShowProject {
+        #[clap(long)]
+        token_mint: Pubkey,
+        #[clap(long)]
+        project_id: u64,
+    },
+    /// Prints the vesting positions bonded by an address
+    ListPositions {
+        #[clap(long)]
+        token_mint: Pubkey,
+        /// [default: the keypair]
+        #[clap(long)]
+        bonder: Option<Pubkey>,
+    },
+    /// Quotes the tokens vested for a bond of lp tokens, or the claimable amount of a position
+    Quote {
+        #[clap(long)]
+        token_mint: Pubkey,
+        #[clap(long)]
+        project_id: u64,
+        #[clap(long, conflicts_with = "bond_id")]
+        lp_amount: Option<u64>,
+        #[clap(long)]
+        bond_id: Option<u64>,
+        /// [default: the keypair]
+        #[clap(long)]
+        bonder: Option<Pubkey>,
+    },
+}
+
+pub fn run(config: &Config, command: Command) -> Result<()> {
+    let payer = config.payer.pubkey();
+    match command {
+        Command::InitProject {
+            token_mint,
+            lp_mint,
+            amount,
+            price,
+            min_discount,
+            max_discount,
+            discount_mode,
+            vesting,
+            token_account,
+            lp_token_account,
+        } => {
+            let project_bonding_id = config
+                .fetch_optional::<ProjectBonds>(&client::project_bonds_address(&token_mint).0)?
+                .map_or(0, |project_bonds| project_bonds.next_bonding_id);
+            let instruction = client::init_new_project(
+                &payer,
+                &token_mint,
+                &token_account.unwrap_or_else(|| get_associated_token_address(&payer, &token_mint)),
+                &lp_mint,
+                &lp_token_account.unwrap_or_else(|| get_associated_token_address(&payer, &lp_mint)),
+                project_bonding_id,
+                amount,
+                price,
+                DiscountSettings {
+                    min_discount,
+                    max_discount,
+                    discount_mode,
+                },
+                (&vesting).into(),
+            );
+            config.send(&[instruction], &[])?;
+            println!("Project id: {}", project_bonding_id);
+        }
+        Command::UpdatePrice {
+            token_mint,
+            project_id,
+            new_price,
+        } => {
+            config.send(&[client::update_price(&payer, &token_mint, project_id, new_price)], &[])?;
+        }
+        Command::Bond {
+            token_mint,
+            project_id,
+            lp_amount,
+            lp_deposit_account,
+        } => {
+            let project_info: ProjectInfo = config.fetch(&client::project_info_address(&token_mint, project_id).0)?;
+            let bond_id = config
+                .fetch_optional::<BondsInfo>(&client::bonds_info_address(&token_mint, &payer).0)?
+                .map_or(0, |bonds_info| bonds_info.total_bonds);
+            let instruction = client::bond(
+                &payer,
+                &token_mint,
+                &project_info.lp_token,
+                &lp_deposit_account.unwrap_or_else(|| get_associated_token_address(&payer, &project_info.lp_token)),
+                &project_info.lp_token_account,
+                project_id,
+                bond_id,
+                lp_amount,
+            );
+            config.send(&[instruction], &[])?;
+            println!("Bond id: {}", bond_id);
+        }
+        Command::Withdraw {
+            token_mint,
+            project_id,
+            bond_id,
+            receive_account,
+        } => {
+            let project_info_key = client::project_info_address(&token_mint, project_id).0;
+            let project_info: ProjectInfo = config.fetch(&project_info_key)?;
+            let milestone_table = milestone_table_key(&project_info);
+            let receive_account = receive_account.unwrap_or_else(|| get_associated_token_address(&payer, &token_mint));
+            let instruction = match bond_id {
+                Some(bond_id) => client::withdraw_vesting(
+                    &payer,
+                    &payer,
+                    &token_mint,
+                    &receive_account,
+                    project_id,
+                    bond_id,
+                    milestone_table,
+                ),
+                None => {
+                    let withdrawable: Vec<(Pubkey, u64)> = positions(config, &token_mint, &payer)?
+                        .into_iter()
+                        .filter(|(_, vesting_info)| {
+                            !vesting_info.is_tokenized()
+                                && vesting_info.current_owner(&payer) == payer
+                                && vesting_info.project_info == project_info_key
+                        })
+                        .map(|(bond_id, _)| (payer, bond_id))
+                        .collect();
+                    if withdrawable.is_empty() {
+                        return Err(anyhow!("no withdrawable positions"));
+                    }
+                    client::withdraw_all_vesting(
+                        &payer,
+                        &token_mint,
+                        &receive_account,
+                        project_id,
+                        &withdrawable,
+                        milestone_table,
+                    )
+                }
+            };
+            config.send(&[instruction], &[])?;
+        }
+        Command::ShowProject { token_mint, project_id } => {
+            let project_info: ProjectInfo = config.fetch(&client::project_info_address(&token_mint, project_id).0)?;
+            println!("Owner: {}", project_info.project_owner);
+            println!("Project token: {}", project_info.project_token);
+            println!("LP token: {}", project_info.lp_token);
+            println!("LP token account: {}", project_info.lp_token_account);
+            println!("Token amount: {}", project_info.token_amount);
+            println!("Vested amount: {}", project_info.vested_amount);
+            println!("Bonded lp amount: {}", project_info.bonded_lp_amount);
+            println!("Price: {}", project_info.price);
+            println!("Discount rate: {} bips", project_info.discount_rate());
+            println!(
+                "Discount: {} - {} bips, mode {}",
+                project_info.min_discout, project_info.max_discount, project_info.discount_mode
+            );
+            println!(
+                "Vesting: interval {}s, rate {}%, instant unlock {}%, initial unlock {}%, lock {}s, period {}s",
+                project_info.release_interval,
+                project_info.release_rate,
+                project_info.instant_unlock,
+                project_info.initial_unlock,
+                project_info.lock_period,
+                project_info.vesting_period
+            );
+            if let Some(milestone_table) = milestone_table_key(&project_info) {
+                println!("Milestone table: {}", milestone_table);
+            }
+        }
+        Command::ListPositions { token_mint, bonder } => {
+            let bonder = bonder.unwrap_or(payer);
+            for (bond_id, vesting_info) in positions(config, &token_mint, &bonder)? {
+                println!(
+                    "Bond {}: project {}, total {}, withdrawn {}, start {}, owner {}{}{}",
+                    bond_id,
+                    if vesting_info.project_info == Pubkey::default() {
+                        "not migrated".to_string()
+                    } else {
+                        vesting_info.project_info.to_string()
+                    },
+                    vesting_info.total_amount,
+                    vesting_info.withdrawn_amount,
+                    vesting_info.start_time,
+                    vesting_info.current_owner(&bonder),
+                    if vesting_info.is_tokenized() { ", tokenized" } else { "" },
+                    if vesting_info.revocable { ", revocable" } else { "" },
+                );
+            }
+        }
+        Command::Quote {
+            token_mint,
+            project_id,
+            lp_amount,
+            bond_id,
+            bonder,
+        } => {
+            let project_info: ProjectInfo = config.fetch(&client::project_info_address(&token_mint, project_id).0)?;
+            match (lp_amount, bond_id) {
+                (Some(lp_amount), _) => {
+                    let vesting_amount = client::bond_vesting_amount(
+                        &project_info,
+                        lp_amount,
+                        config.mint_decimals(&token_mint)?,
+                        config.mint_decimals(&project_info.lp_token)?,
+                    )
+                    .map_err(|err| anyhow!("failed to quote the bond: {}", err))?;
+                    println!("Vesting amount: {}", vesting_amount);
+                }
+                (None, Some(bond_id)) => {
+                    let bonder = bonder.unwrap_or(payer);
+                    let vesting_info: VestingInfo =
+                        config.fetch(&client::vesting_info_address(&token_mint, &bonder, bond_id).0)?;
+                    let milestone_table = milestone_table_key(&project_info)
+                        .map(|key| config.fetch::<MilestoneTable>(&key))
+                        .transpose()?;
+                    let claimable = client::claimable_amount(
+                        &project_info,
+                        &vesting_info,
+                        milestone_table.as_ref(),
+                        config.now()? as u64,
+                    );
+                    println!("Claimable amount: {}", claimable);
+                }
+                (None, None) => return Err(anyhow!("either --lp-amount or --bond-id is required")),
+            }
+        }
+    }
+    Ok(())
+}
+
+fn milestone_table_key(project_info: &ProjectInfo) -> Option<Pubkey> {
+    Some(project_info.milestone_table).filter(|key| *key != Pubkey::default())
+}
+
+/// Open vesting positions of `bonder`; merged positions are closed and skipped.
+fn positions(config: &Config, token_mint: &Pubkey, bonder: &Pubkey) -> Result<Vec<(u64, VestingInfo)>> {
+    let total_bonds = config
+        .fetch_optional::<BondsInfo>(&client::bonds_info_address(token_mint, bonder).0)?
+        .map_or(0, |bonds_info| bonds_info.total_bonds);
+    let mut positions = Vec::new();
+    for bond_id in 0..total_bonds {
+        let key = client::vesting_info_address(token_mint, bonder, bond_id).0;
+        if let Some(vesting_info) = config.fetch_optional::<VestingInfo>(&key)? {
+            positions.push((bond_id, vesting_info));
+        }
+    }
+    Ok(positions)
+}
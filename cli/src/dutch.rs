@@ -0,0 +1,163 @@
+use anchor_spl::associated_token::get_associated_token_address;
+use anyhow::Result;
+use clap::Subcommand;
+use solana_sdk::{pubkey::Pubkey, signature::Signer};
+
+use bonding_client::bond_dutch::{self as client, AuctionInfo, AuctionSettings, ProjectAuctions, VestingSchedule};
+
+use crate::bond::VestingArgs;
+use crate::Config;
+
+#[derive(Subcommand)]
+pub enum Command {
+    /// Creates a dutch auction and deposits its tokens
+    InitAuction {
+        #[clap(long)]
+        token_mint: Pubkey,
+        #[clap(long)]
+        lp_mint: Pubkey,
+        /// Project tokens to deposit
+        #[clap(long)]
+        amount: u64,
+        #[clap(long)]
+        max_price: u64,
+        #[clap(long)]
+        min_price: u64,
+        /// Unix timestamp
+        #[clap(long)]
+        start_time: u64,
+        /// Unix timestamp
+        #[clap(long)]
+        end_time: u64,
+        #[clap(flatten)]
+        vesting: VestingArgs,
+        /// Unix timestamp the vesting starts at
+        #[clap(long)]
+        vesting_start_time: u64,
+        /// Source of the project tokens [default: associated token account]
+        #[clap(long)]
+        token_account: Option<Pubkey>,
+        /// Account receiving the bonded lp tokens [default: associated token account]
+        #[clap(long)]
+        lp_token_account: Option<Pubkey>,
+    },
+    /// Bonds lp tokens into an auction
+    Bond {
+        #[clap(long)]
+        token_mint: Pubkey,
+        #[clap(long)]
+        auction_id: u64,
+        #[clap(long)]
+        lp_amount: u64,
+        /// Source of the lp tokens [default: associated token account]
+        #[clap(long)]
+        lp_deposit_account: Option<Pubkey>,
+    },
+    /// Ends an auction once its end time has passed
+    EndAuction {
+        #[clap(long)]
+        token_mint: Pubkey,
+        #[clap(long)]
+        auction_id: u64,
+    },
+    /// Withdraws the vested tokens of a position
+    Withdraw {
+        #[clap(long)]
+        token_mint: Pubkey,
+        #[clap(long)]
+        auction_id: u64,
+        /// Owner of the position, when claiming as its delegate [default: the keypair]
+        #[clap(long)]
+        owner: Option<Pubkey>,
+        /// Account receiving the project tokens [default: associated token account of the owner]
+        #[clap(long)]
+        receive_account: Option<Pubkey>,
+    },
+}
+
+pub fn run(config: &Config, command: Command) -> Result<()> {
+    let payer = config.payer.pubkey();
+    match command {
+        Command::InitAuction {
+            token_mint,
+            lp_mint,
+            amount,
+            max_price,
+            min_price,
+            start_time,
+            end_time,
+            vesting,
+            vesting_start_time,
+            token_account,
+            lp_token_account,
+        } => {
+            let auction_id = config
+                .fetch_optional::<ProjectAuctions>(&client::project_auctions_address(&token_mint).0)?
+                .map_or(0, |project_auctions| project_auctions.next_auction_id);
+            let instruction = client::init_auction(
+                &payer,
+                &token_mint,
+                &token_account.unwrap_or_else(|| get_associated_token_address(&payer, &token_mint)),
+                &lp_mint,
+                &lp_token_account.unwrap_or_else(|| get_associated_token_address(&payer, &lp_mint)),
+                auction_id,
+                amount,
+                AuctionSettings {
+                    max_price,
+                    min_price,
+                    start_time,
+                    end_time,
+                },
+                VestingSchedule {
+                    release_interval: vesting.release_interval,
+                    release_rate: vesting.release_rate,
+                    instant_unlock: vesting.instant_unlock,
+                    initial_unlock: vesting.initial_unlock,
+                    lock_period: vesting.lock_period,
+                    vesting_period: vesting.vesting_period,
+                    start_time: vesting_start_time,
+                },
+            );
+            config.send(&[instruction], &[])?;
+            println!("Auction id: {}", auction_id);
+        }
+        Command::Bond {
+            token_mint,
+            auction_id,
+            lp_amount,
+            lp_deposit_account,
+        } => {
+            let auction_info: AuctionInfo = config.fetch(&client::auction_info_address(&token_mint, auction_id).0)?;
+            let instruction = client::bond(
+                &payer,
+                &token_mint,
+                &auction_info.lp_token,
+                &lp_deposit_account.unwrap_or_else(|| get_associated_token_address(&payer, &auction_info.lp_token)),
+                &auction_info.lp_token_account,
+                auction_id,
+                lp_amount,
+            );
+            config.send(&[instruction], &[])?;
+        }
+        Command::EndAuction { token_mint, auction_id } => {
+            config.send(&[client::end_auction(&payer, &token_mint, auction_id)], &[])?;
+        }
+        Command::Withdraw {
+            token_mint,
+            auction_id,
+            owner,
+            receive_account,
+        } => {
+            let owner = owner.unwrap_or(payer);
+            let instruction = client::withdraw_vesting(
+                &payer,
+                &owner,
+                &token_mint,
+                &receive_account.unwrap_or_else(|| get_associated_token_address(&owner, &token_mint)),
+                auction_id,
+            );
+            config.send(&[instruction], &[])?;
+        }
+    }
+    Ok(())
+}
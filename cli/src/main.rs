@@ -0,0 +1,131 @@
+//! Command-line tool for project owners and bonders.
+//!
+//! Subcommands are grouped by program and mirror its instructions; token accounts default to the
+//! associated token accounts of the keypair.
+//!
+//! `indicatif` 0.16 (pulled in by `solana-client`) does not build against `console` 0.16, which a
+//! fresh resolve picks; pin it with `cargo update -p console@0.16.6 --precise 0.15.11`.
+
+use anchor_lang::AccountDeserialize;
+use anchor_spl::token::Mint;
+use anyhow::{anyhow, Result};
+use clap::Parser;
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::{
+    account::from_account,
+    clock::Clock,
+    commitment_config::CommitmentConfig,
+    instruction::Instruction,
+    pubkey::Pubkey,
+    signature::{read_keypair_file, Keypair, Signature, Signer},
+    sysvar,
+    transaction::Transaction,
+};
+
+/// auction
+mod auction;
+/// bond
+mod bond;
+/// bond dutch
+mod dutch;
+
+#[derive(Parser)]
+#[clap(name = "bonding", version, about)]
+struct Opts {
+    /// RPC URL of the cluster
+    #[clap(short, long, global = true, default_value = "http://localhost:8899")]
+    url: String,
+    /// Keypair file paying for and signing the transactions
+    #[clap(short, long, global = true, default_value = "~/.config/solana/id.json")]
+    keypair: String,
+    #[clap(subcommand)]
+    command: Command,
+}
+
+#[derive(Parser)]
+enum Command {
+    /// Bond program
+    #[clap(subcommand)]
+    Bond(bond::Command),
+    /// Bond dutch program
+    #[clap(subcommand)]
+    Dutch(dutch::Command),
+    /// Descending auction program
+    #[clap(subcommand)]
+    Auction(auction::Command),
+}
+
+/// Connection to the cluster and the keypair of the user.
+pub struct Config {
+    pub rpc: RpcClient,
+    pub payer: Keypair,
+}
+
+impl Config {
+    /// Fetches and decodes an anchor account.
+    pub fn fetch<T: AccountDeserialize>(&self, key: &Pubkey) -> Result<T> {
+        self.fetch_optional(key)?
+            .ok_or_else(|| anyhow!("account {} not found", key))
+    }
+
+    /// Fetches and decodes an anchor account, `None` when it does not exist.
+    pub fn fetch_optional<T: AccountDeserialize>(&self, key: &Pubkey) -> Result<Option<T>> {
+        let account = self
+            .rpc
+            .get_account_with_commitment(key, self.rpc.commitment())?
+            .value;
+        account
+            .map(|account| {
+                bonding_client::decode_account(&account.data)
+                    .map_err(|err| anyhow!("failed to decode account {}: {}", key, err))
+            })
+            .transpose()
+    }
+
+    pub fn mint_decimals(&self, mint: &Pubkey) -> Result<u8> {
+        Ok(self.fetch::<Mint>(mint)?.decimals)
+    }
+
+    /// Cluster time, as seen by the programs.
+    pub fn now(&self) -> Result<i64> {
+        let account = self.rpc.get_account(&sysvar::clock::ID)?;
+        let clock: Clock = from_account(&account).ok_or_else(|| anyhow!("failed to decode the clock"))?;
+        Ok(clock.unix_timestamp)
+    }
+
+    /// Signs with the payer and `signers`, sends and confirms.
+    pub fn send(&self, instructions: &[Instruction], signers: &[&dyn Signer]) -> Result<Signature> {
+        let mut all_signers: Vec<&dyn Signer> = vec![&self.payer];
+        all_signers.extend_from_slice(signers);
+        let blockhash = self.rpc.get_latest_blockhash()?;
+        let transaction =
+            Transaction::new_signed_with_payer(instructions, Some(&self.payer.pubkey()), &all_signers, blockhash);
+        let signature = self.rpc.send_and_confirm_transaction(&transaction)?;
+        println!("Signature: {}", signature);
+        Ok(signature)
+    }
+}
+
+fn expand_home(path: &str) -> String {
+    match (path.strip_prefix("~/"), std::env::var("HOME")) {
+        (Some(rest), Ok(home)) => format!("{}/{}", home, rest),
+        _ => path.to_string(),
+    }
+}
+
+fn main() -> Result<()> {
+    let opts = Opts::parse();
+    let keypair_path = expand_home(&opts.keypair);
+    let payer = read_keypair_file(&keypair_path)
+        .map_err(|err| anyhow!("failed to read keypair {}: {}", keypair_path, err))?;
+    let config = Config {
+        rpc: RpcClient::new_with_commitment(opts.url, CommitmentConfig::confirmed()),
+        payer,
+    };
+
+    match opts.command {
+        Command::Bond(command) => bond::run(&config, command),
+        Command::Dutch(command) => dutch::run(&config, command),
+        Command::Auction(command) => auction::run(&config, command),
+    }
+}
@@ -0,0 +1,456 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::{instruction::Instruction, system_program, sysvar};
+use anchor_lang::{InstructionData, ToAccountMetas};
+
+pub use descending_auction_program::{
+    Auction, DecayCurve, PricingMode, PurchaseReceipt, VestingSchedule, AUCTION_PREFIX, ID, PURCHASE_RECEIPT_PREFIX,
+};
+
+/*************************** PDAs *************************/
+
+pub fn auction_authority_address(auction: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[AUCTION_PREFIX, &auction.to_bytes()], &ID)
+}
+
+pub fn auction_pool_address(auction: &Pubkey, sale_mint: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[AUCTION_PREFIX, &auction.to_bytes(), &sale_mint.to_bytes()], &ID)
+}
+
+pub fn payment_escrow_address(auction: &Pubkey, payment_mint: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[AUCTION_PREFIX, &auction.to_bytes(), &payment_mint.to_bytes()], &ID)
+}
+
+pub fn purchase_receipt_address(auction: &Pubkey, buyer: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[PURCHASE_RECEIPT_PREFIX, &auction.to_bytes(), &buyer.to_bytes()], &ID)
+}
+
+/*************************** Math *************************/
+
+/// Amount of payment token `purchase` charges for `purchase_amount` at `current_timestamp`, as computed on-chain.
+pub fn payment_amount(auction: &Auction, purchase_amount: u64, sale_decimals: u8, current_timestamp: i64) -> Result<u64> {
+    auction.get_payment_amount_at(purchase_amount, sale_decimals, current_timestamp)
+}
+
+/// Amount of sale token `payment_amount` buys at `current_timestamp`, before the cap at the pool balance.
+pub fn purchase_amount(auction: &Auction, payment_amount: u64, sale_decimals: u8, current_timestamp: i64) -> Result<u64> {
+    let current_price = auction
+        .get_price_at(current_timestamp)
+        .ok_or(descending_auction_program::AuctionError::InternalError)?;
+    Auction::get_purchase_amount_at_price(current_price, payment_amount, sale_decimals)
+}
+
+/*************************** Instructions *************************/
+
+/// Builds `initialize_auction`; `auction` is a new keypair that must sign the transaction.
+#[allow(clippy::too_many_arguments)]
+pub fn initialize_auction(
+    auction: &Pubkey,
+    authority: &Pubkey,
+    payment_mint: &Pubkey,
+    payment_destination: &Pubkey,
+    sale_mint: &Pubkey,
+    start_timestamp: i64,
+    end_timestamp: Option<i64>,
+    ceil_price: u64,
+    floor_price: u64,
+    decay_curve: DecayCurve,
+    pricing_mode: PricingMode,
+    price_hold_duration: i64,
+    price_bump_bps: u64,
+) -> Instruction {
+    let accounts = descending_auction_program::accounts::InitializeAuction {
+        auction: *auction,
+        auction_authority: auction_authority_address(auction).0,
+        authority: *authority,
+        payment_mint: *payment_mint,
+        payment_destination: *payment_destination,
+        sale_mint: *sale_mint,
+        auction_pool: auction_pool_address(auction, sale_mint).0,
+        token_program: anchor_spl::token::ID,
+        system_program: system_program::ID,
+        rent: sysvar::rent::ID,
+    };
+    let data = descending_auction_program::instruction::InitializeAuction {
+        start_timestamp,
+        end_timestamp,
+        ceil_price,
+        floor_price,
+        decay_curve,
+        pricing_mode,
+        price_hold_duration,
+        price_bump_bps,
+    };
+    Instruction {
+        program_id: ID,
+        accounts: accounts.to_account_metas(None),
+        data: data.data(),
+    }
+}
+
+pub fn update_purchase_limits(
+    auction: &Pubkey,
+    authority: &Pubkey,
+    max_per_buyer: Option<u64>,
+    max_per_tx: Option<u64>,
+    min_purchase: u64,
+) -> Instruction {
+    let accounts = descending_auction_program::accounts::UpdatePurchaseLimits {
+        auction: *auction,
+        authority: *authority,
+    };
+    let data = descending_auction_program::instruction::UpdatePurchaseLimits {
+        max_per_buyer,
+        max_per_tx,
+        min_purchase,
+    };
+    Instruction {
+        program_id: ID,
+        accounts: accounts.to_account_metas(None),
+        data: data.data(),
+    }
+}
+
+pub fn update_vesting(auction: &Pubkey, authority: &Pubkey, vesting: Option<VestingSchedule>) -> Instruction {
+    let accounts = descending_auction_program::accounts::UpdateVesting {
+        auction: *auction,
+        authority: *authority,
+    };
+    let data = descending_auction_program::instruction::UpdateVesting { vesting };
+    Instruction {
+        program_id: ID,
+        accounts: accounts.to_account_metas(None),
+        data: data.data(),
+    }
+}
+
+pub fn update_guardian(auction: &Pubkey, authority: &Pubkey, guardian: Option<Pubkey>) -> Instruction {
+    let accounts = descending_auction_program::accounts::UpdateGuardian {
+        auction: *auction,
+        authority: *authority,
+    };
+    let data = descending_auction_program::instruction::UpdateGuardian { guardian };
+    Instruction {
+        program_id: ID,
+        accounts: accounts.to_account_metas(None),
+        data: data.data(),
+    }
+}
+
+pub fn update_end_time(auction: &Pubkey, authority: &Pubkey, end_timestamp: i64) -> Instruction {
+    let accounts = descending_auction_program::accounts::UpdateEndTime {
+        auction: *auction,
+        authority: *authority,
+    };
+    let data = descending_auction_program::instruction::UpdateEndTime { end_timestamp };
+    Instruction {
+        program_id: ID,
+        accounts: accounts.to_account_metas(None),
+        data: data.data(),
+    }
+}
+
+pub fn update_start_time(auction: &Pubkey, authority: &Pubkey, start_timestamp: i64) -> Instruction {
+    let accounts = descending_auction_program::accounts::UpdateStartTime {
+        auction: *auction,
+        authority: *authority,
+    };
+    let data = descending_auction_program::instruction::UpdateStartTime { start_timestamp };
+    Instruction {
+        program_id: ID,
+        accounts: accounts.to_account_metas(None),
+        data: data.data(),
+    }
+}
+
+pub fn update_ceil_price(auction: &Pubkey, authority: &Pubkey, ceil_price: u64) -> Instruction {
+    let accounts = descending_auction_program::accounts::UpdateCeilPrice {
+        auction: *auction,
+        authority: *authority,
+    };
+    let data = descending_auction_program::instruction::UpdateCeilPrice { ceil_price };
+    Instruction {
+        program_id: ID,
+        accounts: accounts.to_account_metas(None),
+        data: data.data(),
+    }
+}
+
+pub fn update_floor_price(auction: &Pubkey, authority: &Pubkey, floor_price: u64) -> Instruction {
+    let accounts = descending_auction_program::accounts::UpdateFloorPrice {
+        auction: *auction,
+        authority: *authority,
+    };
+    let data = descending_auction_program::instruction::UpdateFloorPrice { floor_price };
+    Instruction {
+        program_id: ID,
+        accounts: accounts.to_account_metas(None),
+        data: data.data(),
+    }
+}
+
+/// Builds `cancel_auction`; `canceller` is the authority or the guardian of the auction.
+pub fn cancel_auction(auction: &Pubkey, canceller: &Pubkey) -> Instruction {
+    let accounts = descending_auction_program::accounts::CancelAuction {
+        auction: *auction,
+        canceller: *canceller,
+    };
+    let data = descending_auction_program::instruction::CancelAuction {};
+    Instruction {
+        program_id: ID,
+        accounts: accounts.to_account_metas(None),
+        data: data.data(),
+    }
+}
+
+pub fn end_auction(auction: &Pubkey, authority: &Pubkey) -> Instruction {
+    let accounts = descending_auction_program::accounts::EndAuction {
+        auction: *auction,
+        authority: *authority,
+    };
+    let data = descending_auction_program::instruction::EndAuction {};
+    Instruction {
+        program_id: ID,
+        accounts: accounts.to_account_metas(None),
+        data: data.data(),
+    }
+}
+
+/// Builds `enable_uniform_price`, creating the payment escrow.
+pub fn enable_uniform_price(auction: &Pubkey, authority: &Pubkey, payment_mint: &Pubkey) -> Instruction {
+    let accounts = descending_auction_program::accounts::EnableUniformPrice {
+        auction: *auction,
+        auction_authority: auction_authority_address(auction).0,
+        authority: *authority,
+        payment_mint: *payment_mint,
+        payment_escrow: payment_escrow_address(auction, payment_mint).0,
+        token_program: anchor_spl::token::ID,
+        system_program: system_program::ID,
+        rent: sysvar::rent::ID,
+    };
+    let data = descending_auction_program::instruction::EnableUniformPrice {};
+    Instruction {
+        program_id: ID,
+        accounts: accounts.to_account_metas(None),
+        data: data.data(),
+    }
+}
+
+pub fn deposit_to_auction_pool(
+    auction: &Pubkey,
+    authority: &Pubkey,
+    sale_mint: &Pubkey,
+    source_account: &Pubkey,
+    deposit_amount: u64,
+) -> Instruction {
+    let accounts = descending_auction_program::accounts::DepositToAuctionPool {
+        auction: *auction,
+        auction_pool: auction_pool_address(auction, sale_mint).0,
+        auction_authority: auction_authority_address(auction).0,
+        authority: *authority,
+        source_account: *source_account,
+        token_program: anchor_spl::token::ID,
+    };
+    let data = descending_auction_program::instruction::DepositToAuctionPool { deposit_amount };
+    Instruction {
+        program_id: ID,
+        accounts: accounts.to_account_metas(None),
+        data: data.data(),
+    }
+}
+
+pub fn withdraw_unsold(
+    auction: &Pubkey,
+    authority: &Pubkey,
+    sale_mint: &Pubkey,
+    destination_account: &Pubkey,
+) -> Instruction {
+    let accounts = descending_auction_program::accounts::WithdrawUnsold {
+        auction: *auction,
+        auction_pool: auction_pool_address(auction, sale_mint).0,
+        auction_authority: auction_authority_address(auction).0,
+        authority: *authority,
+        destination_account: *destination_account,
+        token_program: anchor_spl::token::ID,
+    };
+    let data = descending_auction_program::instruction::WithdrawUnsold {};
+    Instruction {
+        program_id: ID,
+        accounts: accounts.to_account_metas(None),
+        data: data.data(),
+    }
+}
+
+pub fn close_auction(
+    auction: &Pubkey,
+    authority: &Pubkey,
+    sale_mint: &Pubkey,
+    destination_account: &Pubkey,
+) -> Instruction {
+    let accounts = descending_auction_program::accounts::CloseAuction {
+        auction: *auction,
+        auction_pool: auction_pool_address(auction, sale_mint).0,
+        auction_authority: auction_authority_address(auction).0,
+        authority: *authority,
+        destination_account: *destination_account,
+        token_program: anchor_spl::token::ID,
+    };
+    let data = descending_auction_program::instruction::CloseAuction {};
+    Instruction {
+        program_id: ID,
+        accounts: accounts.to_account_metas(None),
+        data: data.data(),
+    }
+}
+
+/// Builds `migrate_auction` for an auction still in the `LegacyAuction` layout.
+pub fn migrate_auction(auction: &Pubkey, authority: &Pubkey) -> Instruction {
+    let accounts = descending_auction_program::accounts::MigrateAuction {
+        auction: *auction,
+        authority: *authority,
+        system_program: system_program::ID,
+    };
+    let data = descending_auction_program::instruction::MigrateAuction {};
+    Instruction {
+        program_id: ID,
+        accounts: accounts.to_account_metas(None),
+        data: data.data(),
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn purchase(
+    auction: &Pubkey,
+    auction_state: &Auction,
+    buyer: &Pubkey,
+    payment_source: &Pubkey,
+    sale_destination: &Pubkey,
+    purchase_amount: u64,
+    expected_payment: u64,
+    slippage_tolerance: u64,
+) -> Instruction {
+    let accounts = descending_auction_program::accounts::Purchase {
+        auction: *auction,
+        auction_authority: auction_authority_address(auction).0,
+        sale_mint: auction_state.sale_mint,
+        auction_pool: auction_state.auction_pool,
+        payment_destination: auction_state.get_payment_receiver(),
+        buyer: *buyer,
+        purchase_receipt: purchase_receipt_address(auction, buyer).0,
+        payment_source: *payment_source,
+        sale_destination: *sale_destination,
+        token_program: anchor_spl::token::ID,
+        system_program: system_program::ID,
+    };
+    let data = descending_auction_program::instruction::Purchase {
+        purchase_amount,
+        expected_payment,
+        slippage_tolerance,
+    };
+    Instruction {
+        program_id: ID,
+        accounts: accounts.to_account_metas(None),
+        data: data.data(),
+    }
+}
+
+/// Builds `purchase_exact_payment`, spending at most `payment_amount` for at least `min_purchase_amount`.
+pub fn purchase_exact_payment(
+    auction: &Pubkey,
+    auction_state: &Auction,
+    buyer: &Pubkey,
+    payment_source: &Pubkey,
+    sale_destination: &Pubkey,
+    payment_amount: u64,
+    min_purchase_amount: u64,
+) -> Instruction {
+    let accounts = descending_auction_program::accounts::Purchase {
+        auction: *auction,
+        auction_authority: auction_authority_address(auction).0,
+        sale_mint: auction_state.sale_mint,
+        auction_pool: auction_state.auction_pool,
+        payment_destination: auction_state.get_payment_receiver(),
+        buyer: *buyer,
+        purchase_receipt: purchase_receipt_address(auction, buyer).0,
+        payment_source: *payment_source,
+        sale_destination: *sale_destination,
+        token_program: anchor_spl::token::ID,
+        system_program: system_program::ID,
+    };
+    let data = descending_auction_program::instruction::PurchaseExactPayment {
+        payment_amount,
+        min_purchase_amount,
+    };
+    Instruction {
+        program_id: ID,
+        accounts: accounts.to_account_metas(None),
+        data: data.data(),
+    }
+}
+
+/// Builds `claim_refund` for the purchases of `buyer`; `refund_account` is any payment token account of the buyer.
+pub fn claim_refund(auction: &Pubkey, auction_state: &Auction, buyer: &Pubkey, refund_account: &Pubkey) -> Instruction {
+    let accounts = descending_auction_program::accounts::ClaimRefund {
+        auction: *auction,
+        auction_authority: auction_authority_address(auction).0,
+        sale_mint: auction_state.sale_mint,
+        payment_escrow: auction_state.payment_escrow,
+        purchase_receipt: purchase_receipt_address(auction, buyer).0,
+        refund_account: *refund_account,
+        token_program: anchor_spl::token::ID,
+    };
+    let data = descending_auction_program::instruction::ClaimRefund {};
+    Instruction {
+        program_id: ID,
+        accounts: accounts.to_account_metas(None),
+        data: data.data(),
+    }
+}
+
+pub fn withdraw_proceeds(auction: &Pubkey, auction_state: &Auction) -> Instruction {
+    let accounts = descending_auction_program::accounts::WithdrawProceeds {
+        auction: *auction,
+        auction_authority: auction_authority_address(auction).0,
+        payment_escrow: auction_state.payment_escrow,
+        payment_destination: auction_state.payment_destination,
+        authority: auction_state.authority,
+        token_program: anchor_spl::token::ID,
+    };
+    let data = descending_auction_program::instruction::WithdrawProceeds {};
+    Instruction {
+        program_id: ID,
+        accounts: accounts.to_account_metas(None),
+        data: data.data(),
+    }
+}
+
+pub fn claim(auction: &Pubkey, auction_state: &Auction, buyer: &Pubkey, sale_destination: &Pubkey) -> Instruction {
+    let accounts = descending_auction_program::accounts::Claim {
+        auction: *auction,
+        auction_authority: auction_authority_address(auction).0,
+        auction_pool: auction_state.auction_pool,
+        purchase_receipt: purchase_receipt_address(auction, buyer).0,
+        buyer: *buyer,
+        sale_destination: *sale_destination,
+        token_program: anchor_spl::token::ID,
+    };
+    let data = descending_auction_program::instruction::Claim {};
+    Instruction {
+        program_id: ID,
+        accounts: accounts.to_account_metas(None),
+        data: data.data(),
+    }
+}
+
+/// Builds `quote_purchase`, to be simulated; the return data is the payment amount as a little-endian u64.
+pub fn quote_purchase(auction: &Pubkey, sale_mint: &Pubkey, purchase_amount: u64) -> Instruction {
+    let accounts = descending_auction_program::accounts::QuotePurchase {
+        auction: *auction,
+        sale_mint: *sale_mint,
+    };
+    let data = descending_auction_program::instruction::QuotePurchase { purchase_amount };
+    Instruction {
+        program_id: ID,
+        accounts: accounts.to_account_metas(None),
+        data: data.data(),
+    }
+}
@@ -0,0 +1,668 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::{instruction::Instruction, system_program, sysvar};
+use anchor_lang::{InstructionData, ToAccountMetas};
+use anchor_spl::associated_token::get_associated_token_address;
+
+use ::bond::constant::*;
+pub use ::bond::states::{
+    BondsInfo, DiscountSettings, Milestone, MilestoneTable, PositionInfo, ProjectBonds, ProjectInfo, VestingInfo,
+    VestingSchedule, VestingStatus,
+};
+pub use ::bond::ID;
+
+/*************************** PDAs *************************/
+
+pub fn project_bonds_address(token_mint: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[token_mint.as_ref(), PROJECT_BONDS_SEED], &ID)
+}
+
+pub fn project_info_address(token_mint: &Pubkey, project_bonding_id: u64) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[token_mint.as_ref(), PROJECT_INFO_SEED, project_bonding_id.to_string().as_bytes()],
+        &ID,
+    )
+}
+
+pub fn token_vault_address(token_mint: &Pubkey, project_bonding_id: u64) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[token_mint.as_ref(), TOKEN_VAULT_SEED, project_bonding_id.to_string().as_bytes()],
+        &ID,
+    )
+}
+
+pub fn milestone_table_address(token_mint: &Pubkey, project_bonding_id: u64) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[token_mint.as_ref(), MILESTONE_TABLE_SEED, project_bonding_id.to_string().as_bytes()],
+        &ID,
+    )
+}
+
+pub fn bonds_info_address(token_mint: &Pubkey, bonder: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[token_mint.as_ref(), bonder.as_ref(), BONDS_INFO_SEED], &ID)
+}
+
+pub fn vesting_info_address(token_mint: &Pubkey, bonder: &Pubkey, bond_id: u64) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[token_mint.as_ref(), bonder.as_ref(), VESTING_INFO_SEED, bond_id.to_string().as_bytes()],
+        &ID,
+    )
+}
+
+pub fn position_mint_address(vesting_info: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[vesting_info.as_ref(), POSITION_MINT_SEED], &ID)
+}
+
+/// Reverse lookup of a tokenized position from its mint.
+pub fn position_info_address(position_mint: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[position_mint.as_ref(), POSITION_INFO_SEED], &ID)
+}
+
+/*************************** Math *************************/
+
+/// Amount of project tokens `bond` vests for `lp_amount`, as computed on-chain.
+pub fn bond_vesting_amount(project_info: &ProjectInfo, lp_amount: u64, token_decimals: u8, lp_decimals: u8) -> Result<u64> {
+    project_info.bond_vesting_amount(lp_amount, token_decimals, lp_decimals)
+}
+
+/// Amount `withdraw_vesting` would transfer at `now`, as computed on-chain.
+pub fn claimable_amount(
+    project_info: &ProjectInfo,
+    vesting_info: &VestingInfo,
+    milestone_table: Option<&MilestoneTable>,
+    now: u64,
+) -> u64 {
+    vesting_info.vested_amount(project_info, now, ACCURACY, milestone_table) - vesting_info.withdrawn_amount
+}
+
+/*************************** Instructions *************************/
+
+/// Builds `init_new_project`; `project_bonding_id` is the current `ProjectBonds::next_bonding_id` of the mint.
+#[allow(clippy::too_many_arguments)]
+pub fn init_new_project(
+    initializer: &Pubkey,
+    token_mint: &Pubkey,
+    token_account: &Pubkey,
+    lp_mint: &Pubkey,
+    lp_token_account: &Pubkey,
+    project_bonding_id: u64,
+    amount: u64,
+    price: u64,
+    discount_settings: DiscountSettings,
+    vesting_schedule: VestingSchedule,
+) -> Instruction {
+    let accounts = ::bond::accounts::InitNewProject {
+        initializer: *initializer,
+        token_mint: *token_mint,
+        token_account: *token_account,
+        lp_mint: *lp_mint,
+        lp_token_account: *lp_token_account,
+        project_bonds: project_bonds_address(token_mint).0,
+        vault_account: token_vault_address(token_mint, project_bonding_id).0,
+        project_info: project_info_address(token_mint, project_bonding_id).0,
+        rent: sysvar::rent::ID,
+        system_program: system_program::ID,
+        token_program: anchor_spl::token::ID,
+    };
+    let data = ::bond::instruction::InitNewProject {
+        amount,
+        price,
+        discount_settings,
+        vesting_schedule,
+    };
+    Instruction {
+        program_id: ID,
+        accounts: accounts.to_account_metas(None),
+        data: data.data(),
+    }
+}
+
+pub fn update_price(user: &Pubkey, token_mint: &Pubkey, project_bonding_id: u64, new_price: u64) -> Instruction {
+    let accounts = ::bond::accounts::UpdatePrice {
+        user: *user,
+        project_info: project_info_address(token_mint, project_bonding_id).0,
+    };
+    let data = ::bond::instruction::UpdatePrice {
+        _project_bonding_id: project_bonding_id,
+        new_price,
+    };
+    Instruction {
+        program_id: ID,
+        accounts: accounts.to_account_metas(None),
+        data: data.data(),
+    }
+}
+
+pub fn update_authority(user: &Pubkey, token_mint: &Pubkey, project_bonding_id: u64, new_authority: Pubkey) -> Instruction {
+    let accounts = ::bond::accounts::UpdateAuthority {
+        user: *user,
+        project_info: project_info_address(token_mint, project_bonding_id).0,
+    };
+    let data = ::bond::instruction::UpdateAuthority {
+        _project_bonding_id: project_bonding_id,
+        new_authority,
+    };
+    Instruction {
+        program_id: ID,
+        accounts: accounts.to_account_metas(None),
+        data: data.data(),
+    }
+}
+
+/// Builds `init_milestone_table`, before the project has bonds.
+pub fn init_milestone_table(
+    user: &Pubkey,
+    token_mint: &Pubkey,
+    project_bonding_id: u64,
+    time_mode: u64,
+    milestones: Vec<Milestone>,
+) -> Instruction {
+    let accounts = ::bond::accounts::InitMilestoneTable {
+        user: *user,
+        project_info: project_info_address(token_mint, project_bonding_id).0,
+        milestone_table: milestone_table_address(token_mint, project_bonding_id).0,
+        system_program: system_program::ID,
+    };
+    let data = ::bond::instruction::InitMilestoneTable {
+        _project_bonding_id: project_bonding_id,
+        time_mode,
+        milestones,
+    };
+    Instruction {
+        program_id: ID,
+        accounts: accounts.to_account_metas(None),
+        data: data.data(),
+    }
+}
+
+/// Builds `bond`; `bond_id` is the current `BondsInfo::total_bonds` of the user, 0 for a first bond.
+#[allow(clippy::too_many_arguments)]
+pub fn bond(
+    user: &Pubkey,
+    token_mint: &Pubkey,
+    lp_mint: &Pubkey,
+    lp_deposit_account: &Pubkey,
+    lp_recieve_account: &Pubkey,
+    project_bonding_id: u64,
+    bond_id: u64,
+    lp_amount: u64,
+) -> Instruction {
+    let accounts = ::bond::accounts::Bond {
+        user: *user,
+        lp_mint: *lp_mint,
+        lp_deposit_account: *lp_deposit_account,
+        lp_recieve_account: *lp_recieve_account,
+        token_mint: *token_mint,
+        project_info: project_info_address(token_mint, project_bonding_id).0,
+        bonds_info: bonds_info_address(token_mint, user).0,
+        vesting_info: vesting_info_address(token_mint, user, bond_id).0,
+        system_program: system_program::ID,
+        token_program: anchor_spl::token::ID,
+        clock: sysvar::clock::ID,
+    };
+    let data = ::bond::instruction::Bond {
+        _project_bonding_id: project_bonding_id,
+        lp_amount,
+    };
+    Instruction {
+        program_id: ID,
+        accounts: accounts.to_account_metas(None),
+        data: data.data(),
+    }
+}
+
+/// Builds `bond_for`; `bond_id` is the current `BondsInfo::total_bonds` of the beneficiary, 0 for a first bond.
+#[allow(clippy::too_many_arguments)]
+pub fn bond_for(
+    user: &Pubkey,
+    beneficiary: &Pubkey,
+    token_mint: &Pubkey,
+    lp_mint: &Pubkey,
+    lp_deposit_account: &Pubkey,
+    lp_recieve_account: &Pubkey,
+    project_bonding_id: u64,
+    bond_id: u64,
+    lp_amount: u64,
+) -> Instruction {
+    let accounts = ::bond::accounts::BondFor {
+        user: *user,
+        lp_mint: *lp_mint,
+        lp_deposit_account: *lp_deposit_account,
+        lp_recieve_account: *lp_recieve_account,
+        token_mint: *token_mint,
+        project_info: project_info_address(token_mint, project_bonding_id).0,
+        bonds_info: bonds_info_address(token_mint, beneficiary).0,
+        vesting_info: vesting_info_address(token_mint, beneficiary, bond_id).0,
+        system_program: system_program::ID,
+        token_program: anchor_spl::token::ID,
+        clock: sysvar::clock::ID,
+    };
+    let data = ::bond::instruction::BondFor {
+        _project_bonding_id: project_bonding_id,
+        lp_amount,
+        beneficiary: *beneficiary,
+    };
+    Instruction {
+        program_id: ID,
+        accounts: accounts.to_account_metas(None),
+        data: data.data(),
+    }
+}
+
+/// Builds `bond_with_position_mint`; the position token goes to the user's associated token account.
+#[allow(clippy::too_many_arguments)]
+pub fn bond_with_position_mint(
+    user: &Pubkey,
+    token_mint: &Pubkey,
+    lp_mint: &Pubkey,
+    lp_deposit_account: &Pubkey,
+    lp_recieve_account: &Pubkey,
+    project_bonding_id: u64,
+    bond_id: u64,
+    lp_amount: u64,
+) -> Instruction {
+    let vesting_info = vesting_info_address(token_mint, user, bond_id).0;
+    let position_mint = position_mint_address(&vesting_info).0;
+    let accounts = ::bond::accounts::BondWithPositionMint {
+        user: *user,
+        lp_mint: *lp_mint,
+        lp_deposit_account: *lp_deposit_account,
+        lp_recieve_account: *lp_recieve_account,
+        token_mint: *token_mint,
+        project_info: project_info_address(token_mint, project_bonding_id).0,
+        bonds_info: bonds_info_address(token_mint, user).0,
+        vesting_info,
+        position_mint,
+        position_info: position_info_address(&position_mint).0,
+        position_token_account: get_associated_token_address(user, &position_mint),
+        system_program: system_program::ID,
+        token_program: anchor_spl::token::ID,
+        associated_token_program: anchor_spl::associated_token::ID,
+        rent: sysvar::rent::ID,
+        clock: sysvar::clock::ID,
+    };
+    let data = ::bond::instruction::BondWithPositionMint {
+        _project_bonding_id: project_bonding_id,
+        lp_amount,
+    };
+    Instruction {
+        program_id: ID,
+        accounts: accounts.to_account_metas(None),
+        data: data.data(),
+    }
+}
+
+/// Builds `withdraw_vesting`; pass the project's milestone table when it has one.
+pub fn withdraw_vesting(
+    taker: &Pubkey,
+    bonder: &Pubkey,
+    token_mint: &Pubkey,
+    taker_receive_token_account: &Pubkey,
+    project_bonding_id: u64,
+    bond_id: u64,
+    milestone_table: Option<Pubkey>,
+) -> Instruction {
+    let accounts = ::bond::accounts::WithdrawVesting {
+        taker: *taker,
+        bonder: *bonder,
+        taker_receive_token_account: *taker_receive_token_account,
+        vault_account: token_vault_address(token_mint, project_bonding_id).0,
+        project_info: project_info_address(token_mint, project_bonding_id).0,
+        vesting_info: vesting_info_address(token_mint, bonder, bond_id).0,
+        token_program: anchor_spl::token::ID,
+        clock: sysvar::clock::ID,
+    };
+    let mut account_metas = accounts.to_account_metas(None);
+    account_metas.extend(milestone_table.map(|key| AccountMeta::new_readonly(key, false)));
+    let data = ::bond::instruction::WithdrawVesting {
+        project_bonding_id,
+        _bond_id: bond_id,
+    };
+    Instruction {
+        program_id: ID,
+        accounts: account_metas,
+        data: data.data(),
+    }
+}
+
+/// Builds `withdraw_all_vesting` over positions owned by `taker`, given as `(bonder, bond_id)` pairs;
+/// pass the project's milestone table when it has one.
+pub fn withdraw_all_vesting(
+    taker: &Pubkey,
+    token_mint: &Pubkey,
+    taker_receive_token_account: &Pubkey,
+    project_bonding_id: u64,
+    positions: &[(Pubkey, u64)],
+    milestone_table: Option<Pubkey>,
+) -> Instruction {
+    let accounts = ::bond::accounts::WithdrawAllVesting {
+        taker: *taker,
+        taker_receive_token_account: *taker_receive_token_account,
+        vault_account: token_vault_address(token_mint, project_bonding_id).0,
+        project_info: project_info_address(token_mint, project_bonding_id).0,
+        token_program: anchor_spl::token::ID,
+        clock: sysvar::clock::ID,
+    };
+    let mut account_metas = accounts.to_account_metas(None);
+    account_metas.extend(milestone_table.map(|key| AccountMeta::new_readonly(key, false)));
+    account_metas.extend(
+        positions
+            .iter()
+            .map(|(bonder, bond_id)| AccountMeta::new(vesting_info_address(token_mint, bonder, *bond_id).0, false)),
+    );
+    let data = ::bond::instruction::WithdrawAllVesting {
+        project_bonding_id,
+        bond_ids: positions.iter().map(|(_, bond_id)| *bond_id).collect(),
+    };
+    Instruction {
+        program_id: ID,
+        accounts: account_metas,
+        data: data.data(),
+    }
+}
+
+/// Builds `withdraw_vesting_with_position` for the holder of the position token;
+/// pass the project's milestone table when it has one.
+#[allow(clippy::too_many_arguments)]
+pub fn withdraw_vesting_with_position(
+    holder: &Pubkey,
+    position_token_account: &Pubkey,
+    bonder: &Pubkey,
+    token_mint: &Pubkey,
+    holder_receive_token_account: &Pubkey,
+    project_bonding_id: u64,
+    bond_id: u64,
+    milestone_table: Option<Pubkey>,
+) -> Instruction {
+    let accounts = ::bond::accounts::WithdrawVestingWithPosition {
+        holder: *holder,
+        position_token_account: *position_token_account,
+        bonder: *bonder,
+        holder_receive_token_account: *holder_receive_token_account,
+        vault_account: token_vault_address(token_mint, project_bonding_id).0,
+        project_info: project_info_address(token_mint, project_bonding_id).0,
+        vesting_info: vesting_info_address(token_mint, bonder, bond_id).0,
+        token_program: anchor_spl::token::ID,
+        clock: sysvar::clock::ID,
+    };
+    let mut account_metas = accounts.to_account_metas(None);
+    account_metas.extend(milestone_table.map(|key| AccountMeta::new_readonly(key, false)));
+    let data = ::bond::instruction::WithdrawVestingWithPosition {
+        project_bonding_id,
+        _bond_id: bond_id,
+    };
+    Instruction {
+        program_id: ID,
+        accounts: account_metas,
+        data: data.data(),
+    }
+}
+
+/// Builds `merge_vesting`, merging positions owned by `user`, given as `(bonder, bond_id)` pairs,
+/// into the position `bond_id` bonded by `bonder`.
+pub fn merge_vesting(
+    user: &Pubkey,
+    bonder: &Pubkey,
+    token_mint: &Pubkey,
+    project_bonding_id: u64,
+    bond_id: u64,
+    merged_positions: &[(Pubkey, u64)],
+) -> Instruction {
+    let accounts = ::bond::accounts::MergeVesting {
+        user: *user,
+        bonder: *bonder,
+        project_info: project_info_address(token_mint, project_bonding_id).0,
+        vesting_info: vesting_info_address(token_mint, bonder, bond_id).0,
+    };
+    let mut account_metas = accounts.to_account_metas(None);
+    account_metas.extend(
+        merged_positions
+            .iter()
+            .map(|(bonder, bond_id)| AccountMeta::new(vesting_info_address(token_mint, bonder, *bond_id).0, false)),
+    );
+    let data = ::bond::instruction::MergeVesting {
+        _project_bonding_id: project_bonding_id,
+        _bond_id: bond_id,
+        merged_bond_ids: merged_positions.iter().map(|(_, bond_id)| *bond_id).collect(),
+    };
+    Instruction {
+        program_id: ID,
+        accounts: account_metas,
+        data: data.data(),
+    }
+}
+
+pub fn transfer_vesting(
+    owner: &Pubkey,
+    bonder: &Pubkey,
+    token_mint: &Pubkey,
+    project_bonding_id: u64,
+    bond_id: u64,
+    new_owner: Pubkey,
+) -> Instruction {
+    let accounts = ::bond::accounts::TransferVesting {
+        owner: *owner,
+        bonder: *bonder,
+        project_info: project_info_address(token_mint, project_bonding_id).0,
+        vesting_info: vesting_info_address(token_mint, bonder, bond_id).0,
+    };
+    let data = ::bond::instruction::TransferVesting {
+        _project_bonding_id: project_bonding_id,
+        _bond_id: bond_id,
+        new_owner,
+    };
+    Instruction {
+        program_id: ID,
+        accounts: accounts.to_account_metas(None),
+        data: data.data(),
+    }
+}
+
+/// Builds `set_claim_delegate`; `None` removes the delegate.
+pub fn set_claim_delegate(
+    owner: &Pubkey,
+    bonder: &Pubkey,
+    token_mint: &Pubkey,
+    project_bonding_id: u64,
+    bond_id: u64,
+    delegate: Option<Pubkey>,
+) -> Instruction {
+    let accounts = ::bond::accounts::SetClaimDelegate {
+        owner: *owner,
+        bonder: *bonder,
+        project_info: project_info_address(token_mint, project_bonding_id).0,
+        vesting_info: vesting_info_address(token_mint, bonder, bond_id).0,
+    };
+    let data = ::bond::instruction::SetClaimDelegate {
+        _project_bonding_id: project_bonding_id,
+        _bond_id: bond_id,
+        delegate,
+    };
+    Instruction {
+        program_id: ID,
+        accounts: accounts.to_account_metas(None),
+        data: data.data(),
+    }
+}
+
+/// Builds `create_grant`; `bond_id` is the current `BondsInfo::total_bonds` of the grantee, 0 for a first position.
+pub fn create_grant(
+    user: &Pubkey,
+    grantee: &Pubkey,
+    token_mint: &Pubkey,
+    project_bonding_id: u64,
+    bond_id: u64,
+    amount: u64,
+) -> Instruction {
+    let accounts = ::bond::accounts::CreateGrant {
+        user: *user,
+        project_info: project_info_address(token_mint, project_bonding_id).0,
+        bonds_info: bonds_info_address(token_mint, grantee).0,
+        vesting_info: vesting_info_address(token_mint, grantee, bond_id).0,
+        system_program: system_program::ID,
+        clock: sysvar::clock::ID,
+    };
+    let data = ::bond::instruction::CreateGrant {
+        _project_bonding_id: project_bonding_id,
+        grantee: *grantee,
+        amount,
+    };
+    Instruction {
+        program_id: ID,
+        accounts: accounts.to_account_metas(None),
+        data: data.data(),
+    }
+}
+
+/// Builds `revoke` of the grant `bond_id` of `bonder`; pass the project's milestone table when it has one.
+#[allow(clippy::too_many_arguments)]
+pub fn revoke(
+    user: &Pubkey,
+    bonder: &Pubkey,
+    token_mint: &Pubkey,
+    grantee_receive_token_account: &Pubkey,
+    owner_receive_token_account: &Pubkey,
+    project_bonding_id: u64,
+    bond_id: u64,
+    milestone_table: Option<Pubkey>,
+) -> Instruction {
+    let accounts = ::bond::accounts::Revoke {
+        user: *user,
+        bonder: *bonder,
+        grantee_receive_token_account: *grantee_receive_token_account,
+        owner_receive_token_account: *owner_receive_token_account,
+        vault_account: token_vault_address(token_mint, project_bonding_id).0,
+        project_info: project_info_address(token_mint, project_bonding_id).0,
+        vesting_info: vesting_info_address(token_mint, bonder, bond_id).0,
+        token_program: anchor_spl::token::ID,
+        clock: sysvar::clock::ID,
+    };
+    let mut account_metas = accounts.to_account_metas(None);
+    account_metas.extend(milestone_table.map(|key| AccountMeta::new_readonly(key, false)));
+    let data = ::bond::instruction::Revoke {
+        project_bonding_id,
+        _bond_id: bond_id,
+    };
+    Instruction {
+        program_id: ID,
+        accounts: account_metas,
+        data: data.data(),
+    }
+}
+
+/// Builds `migrate_vesting`, signed by both the project owner and the owner of the position.
+/// `withdrawn_amount` is what the position was already paid out, read from the vault's transfer history.
+pub fn migrate_vesting(
+    user: &Pubkey,
+    owner: &Pubkey,
+    bonder: &Pubkey,
+    token_mint: &Pubkey,
+    project_bonding_id: u64,
+    bond_id: u64,
+    withdrawn_amount: u64,
+) -> Instruction {
+    let accounts = ::bond::accounts::MigrateVesting {
+        user: *user,
+        owner: *owner,
+        bonder: *bonder,
+        project_info: project_info_address(token_mint, project_bonding_id).0,
+        vesting_info: vesting_info_address(token_mint, bonder, bond_id).0,
+    };
+    let data = ::bond::instruction::MigrateVesting {
+        _project_bonding_id: project_bonding_id,
+        _bond_id: bond_id,
+        withdrawn_amount,
+    };
+    Instruction {
+        program_id: ID,
+        accounts: accounts.to_account_metas(None),
+        data: data.data(),
+    }
+}
+
+/// Builds `store_project_bumps` for a project created before bumps were stored.
+pub fn store_project_bumps(token_mint: &Pubkey, project_bonding_id: u64) -> Instruction {
+    let accounts = ::bond::accounts::StoreProjectBumps {
+        project_info: project_info_address(token_mint, project_bonding_id).0,
+        vault_account: token_vault_address(token_mint, project_bonding_id).0,
+    };
+    let data = ::bond::instruction::StoreProjectBumps {
+        _project_bonding_id: project_bonding_id,
+    };
+    Instruction {
+        program_id: ID,
+        accounts: accounts.to_account_metas(None),
+        data: data.data(),
+    }
+}
+
+/// Builds `quote_bond`, to be simulated; the return data is the vested amount as a little-endian u64.
+pub fn quote_bond(token_mint: &Pubkey, lp_mint: &Pubkey, project_bonding_id: u64, lp_amount: u64) -> Instruction {
+    let accounts = ::bond::accounts::QuoteBond {
+        lp_mint: *lp_mint,
+        token_mint: *token_mint,
+        project_info: project_info_address(token_mint, project_bonding_id).0,
+    };
+    let data = ::bond::instruction::QuoteBond {
+        _project_bonding_id: project_bonding_id,
+        lp_amount,
+    };
+    Instruction {
+        program_id: ID,
+        accounts: accounts.to_account_metas(None),
+        data: data.data(),
+    }
+}
+
+/// Builds `view_vesting`, to be simulated or called over CPI; the return data is a `VestingStatus`.
+pub fn view_vesting(
+    bonder: &Pubkey,
+    token_mint: &Pubkey,
+    project_bonding_id: u64,
+    bond_id: u64,
+    milestone_table: Option<Pubkey>,
+) -> Instruction {
+    let accounts = ::bond::accounts::ViewVesting {
+        bonder: *bonder,
+        project_info: project_info_address(token_mint, project_bonding_id).0,
+        vesting_info: vesting_info_address(token_mint, bonder, bond_id).0,
+        clock: sysvar::clock::ID,
+    };
+    let mut account_metas = accounts.to_account_metas(None);
+    account_metas.extend(milestone_table.map(|key| AccountMeta::new_readonly(key, false)));
+    let data = ::bond::instruction::ViewVesting {
+        _project_bonding_id: project_bonding_id,
+        _bond_id: bond_id,
+    };
+    Instruction {
+        program_id: ID,
+        accounts: account_metas,
+        data: data.data(),
+    }
+}
+
+/// Builds `quote_claimable`, to be simulated; the return data is the claimable amount as a little-endian u64.
+pub fn quote_claimable(
+    bonder: &Pubkey,
+    token_mint: &Pubkey,
+    project_bonding_id: u64,
+    bond_id: u64,
+    milestone_table: Option<Pubkey>,
+) -> Instruction {
+    let accounts = ::bond::accounts::ViewVesting {
+        bonder: *bonder,
+        project_info: project_info_address(token_mint, project_bonding_id).0,
+        vesting_info: vesting_info_address(token_mint, bonder, bond_id).0,
+        clock: sysvar::clock::ID,
+    };
+    let mut account_metas = accounts.to_account_metas(None);
+    account_metas.extend(milestone_table.map(|key| AccountMeta::new_readonly(key, false)));
+    let data = ::bond::instruction::QuoteClaimable {
+        _project_bonding_id: project_bonding_id,
+        _bond_id: bond_id,
+    };
+    Instruction {
+        program_id: ID,
+        accounts: account_metas,
+        data: data.data(),
+    }
+}
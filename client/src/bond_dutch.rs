@@ -0,0 +1,230 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::{instruction::Instruction, system_program, sysvar};
+use anchor_lang::{InstructionData, ToAccountMetas};
+
+use bond_dutch::constant::*;
+pub use bond_dutch::states::{AuctionInfo, AuctionSettings, ProjectAuctions, VestingInfo, VestingSchedule};
+pub use bond_dutch::ID;
+
+/*************************** PDAs *************************/
+
+pub fn project_auctions_address(token_mint: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[token_mint.as_ref(), PROJECT_BONDS_SEED], &ID)
+}
+
+pub fn auction_info_address(token_mint: &Pubkey, auction_id: u64) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[token_mint.as_ref(), PROJECT_INFO_SEED, auction_id.to_string().as_bytes()],
+        &ID,
+    )
+}
+
+pub fn token_vault_address(token_mint: &Pubkey, auction_id: u64) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[token_mint.as_ref(), TOKEN_VAULT_SEED, auction_id.to_string().as_bytes()],
+        &ID,
+    )
+}
+
+/// Vesting info of `owner` in an auction, accumulating all its bonds
+pub fn vesting_info_address(token_mint: &Pubkey, owner: &Pubkey, auction_id: u64) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[token_mint.as_ref(), owner.as_ref(), VESTING_INFO_SEED, auction_id.to_string().as_bytes()],
+        &ID,
+    )
+}
+
+/// Vesting info bonded before positions were seeded by auction id, moved by `migrate_vesting`
+pub fn legacy_vesting_info_address(token_mint: &Pubkey, owner: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[token_mint.as_ref(), owner.as_ref(), VESTING_INFO_SEED], &ID)
+}
+
+/*************************** Instructions *************************/
+
+/// Builds `init_auction`; `auction_id` is the current `ProjectAuctions::next_auction_id` of the mint.
+#[allow(clippy::too_many_arguments)]
+pub fn init_auction(
+    initializer: &Pubkey,
+    token_mint: &Pubkey,
+    token_account: &Pubkey,
+    lp_mint: &Pubkey,
+    lp_token_account: &Pubkey,
+    auction_id: u64,
+    amount: u64,
+    auction_settings: AuctionSettings,
+    vesting_schedule: VestingSchedule,
+) -> Instruction {
+    let accounts = bond_dutch::accounts::InitAuction {
+        initializer: *initializer,
+        token_mint: *token_mint,
+        token_account: *token_account,
+        lp_mint: *lp_mint,
+        lp_token_account: *lp_token_account,
+        project_auctions: project_auctions_address(token_mint).0,
+        vault_account: token_vault_address(token_mint, auction_id).0,
+        auction_info: auction_info_address(token_mint, auction_id).0,
+        rent: sysvar::rent::ID,
+        system_program: system_program::ID,
+        token_program: anchor_spl::token::ID,
+    };
+    let data = bond_dutch::instruction::InitAuction {
+        amount,
+        auction_settings,
+        vesting_schedule,
+    };
+    Instruction {
+        program_id: ID,
+        accounts: accounts.to_account_metas(None),
+        data: data.data(),
+    }
+}
+
+pub fn update_authority(user: &Pubkey, token_mint: &Pubkey, auction_id: u64, new_authority: Pubkey) -> Instruction {
+    let accounts = bond_dutch::accounts::UpdateAuthority {
+        user: *user,
+        auction_info: auction_info_address(token_mint, auction_id).0,
+    };
+    let data = bond_dutch::instruction::UpdateAuthority {
+        _auction_id: auction_id,
+        new_authority,
+    };
+    Instruction {
+        program_id: ID,
+        accounts: accounts.to_account_metas(None),
+        data: data.data(),
+    }
+}
+
+pub fn update_settings(
+    user: &Pubkey,
+    token_mint: &Pubkey,
+    auction_id: u64,
+    auction_settings: AuctionSettings,
+    vesting_schedule: VestingSchedule,
+) -> Instruction {
+    let accounts = bond_dutch::accounts::UpdateSettings {
+        user: *user,
+        auction_info: auction_info_address(token_mint, auction_id).0,
+    };
+    let data = bond_dutch::instruction::UpdateSettings {
+        _auction_id: auction_id,
+        auction_settings,
+        vesting_schedule,
+    };
+    Instruction {
+        program_id: ID,
+        accounts: accounts.to_account_metas(None),
+        data: data.data(),
+    }
+}
+
+pub fn bond(
+    user: &Pubkey,
+    token_mint: &Pubkey,
+    lp_mint: &Pubkey,
+    lp_deposit_account: &Pubkey,
+    lp_recieve_account: &Pubkey,
+    auction_id: u64,
+    lp_amount: u64,
+) -> Instruction {
+    let accounts = bond_dutch::accounts::Bond {
+        user: *user,
+        lp_mint: *lp_mint,
+        lp_deposit_account: *lp_deposit_account,
+        lp_recieve_account: *lp_recieve_account,
+        token_mint: *token_mint,
+        auction_info: auction_info_address(token_mint, auction_id).0,
+        vesting_info: vesting_info_address(token_mint, user, auction_id).0,
+        system_program: system_program::ID,
+        token_program: anchor_spl::token::ID,
+        clock: sysvar::clock::ID,
+    };
+    let data = bond_dutch::instruction::Bond {
+        _auction_id: auction_id,
+        lp_amount,
+    };
+    Instruction {
+        program_id: ID,
+        accounts: accounts.to_account_metas(None),
+        data: data.data(),
+    }
+}
+
+pub fn end_auction(initializer: &Pubkey, token_mint: &Pubkey, auction_id: u64) -> Instruction {
+    let accounts = bond_dutch::accounts::EndAuction {
+        initializer: *initializer,
+        auction_info: auction_info_address(token_mint, auction_id).0,
+        clock: sysvar::clock::ID,
+    };
+    let data = bond_dutch::instruction::EndAuction {
+        _auction_id: auction_id,
+    };
+    Instruction {
+        program_id: ID,
+        accounts: accounts.to_account_metas(None),
+        data: data.data(),
+    }
+}
+
+pub fn withdraw_vesting(
+    taker: &Pubkey,
+    owner: &Pubkey,
+    token_mint: &Pubkey,
+    taker_receive_token_account: &Pubkey,
+    auction_id: u64,
+) -> Instruction {
+    let accounts = bond_dutch::accounts::WithdrawVesting {
+        taker: *taker,
+        owner: *owner,
+        taker_receive_token_account: *taker_receive_token_account,
+        vault_account: token_vault_address(token_mint, auction_id).0,
+        auction_info: auction_info_address(token_mint, auction_id).0,
+        vesting_info: vesting_info_address(token_mint, owner, auction_id).0,
+        token_program: anchor_spl::token::ID,
+        clock: sysvar::clock::ID,
+    };
+    let data = bond_dutch::instruction::WithdrawVesting { auction_id };
+    Instruction {
+        program_id: ID,
+        accounts: accounts.to_account_metas(None),
+        data: data.data(),
+    }
+}
+
+/// Builds `set_claim_delegate`; `None` removes the delegate.
+pub fn set_claim_delegate(owner: &Pubkey, token_mint: &Pubkey, auction_id: u64, delegate: Option<Pubkey>) -> Instruction {
+    let accounts = bond_dutch::accounts::SetClaimDelegate {
+        owner: *owner,
+        auction_info: auction_info_address(token_mint, auction_id).0,
+        vesting_info: vesting_info_address(token_mint, owner, auction_id).0,
+    };
+    let data = bond_dutch::instruction::SetClaimDelegate {
+        _auction_id: auction_id,
+        delegate,
+    };
+    Instruction {
+        program_id: ID,
+        accounts: accounts.to_account_metas(None),
+        data: data.data(),
+    }
+}
+
+/// Builds `migrate_vesting`, signed by both the project owner and the owner of the legacy position.
+pub fn migrate_vesting(user: &Pubkey, owner: &Pubkey, token_mint: &Pubkey, auction_id: u64) -> Instruction {
+    let accounts = bond_dutch::accounts::MigrateVesting {
+        user: *user,
+        owner: *owner,
+        auction_info: auction_info_address(token_mint, auction_id).0,
+        legacy_vesting_info: legacy_vesting_info_address(token_mint, owner).0,
+        vesting_info: vesting_info_address(token_mint, owner, auction_id).0,
+        system_program: system_program::ID,
+    };
+    let data = bond_dutch::instruction::MigrateVesting {
+        _auction_id: auction_id,
+    };
+    Instruction {
+        program_id: ID,
+        accounts: accounts.to_account_metas(None),
+        data: data.data(),
+    }
+}
@@ -0,0 +1,19 @@
+//! Off-chain helpers shared by services talking to the bonding programs:
+//! PDA derivation, instruction builders, account decoders and the on-chain pricing and vesting math.
+// anchor's `Error` is large; the helpers return it as the programs do
+#![allow(clippy::result_large_err)]
+
+use anchor_lang::prelude::*;
+
+/// auction
+pub mod auction;
+/// bond
+pub mod bond;
+/// bond dutch
+pub mod bond_dutch;
+
+/// Decodes an anchor account, checking its discriminator.
+pub fn decode_account<T: AccountDeserialize>(data: &[u8]) -> Result<T> {
+    let mut data = data;
+    T::try_deserialize(&mut data)
+}
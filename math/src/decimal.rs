@@ -0,0 +1,345 @@
+use borsh::{BorshDeserialize, BorshSerialize};
+use std::convert::TryFrom;
+
+mod u192 {
+    #![allow(clippy::assign_op_pattern, clippy::ptr_offset_with_cast, clippy::manual_range_contains)]
+    uint::construct_uint! {
+        pub struct U192(3);
+    }
+}
+use u192::U192;
+
+/// Number of decimal places of a `Decimal`
+pub const SCALE: u32 = 18;
+/// 1.0 as a raw `Decimal` value
+pub const WAD: u128 = 1_000_000_000_000_000_000;
+/// Basis points in 1.0
+pub const BPS: u64 = 10_000;
+/// ln(2) as a raw `Decimal` value
+pub const LN_2: u128 = 693_147_180_559_945_309;
+
+/// Direction to round a `Decimal` to an integer amount.
+/// Callers round against the user: down for amounts paid out, up for amounts paid in.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Rounding {
+    Down,
+    Up,
+}
+
+/// Unsigned fixed-point number with 18 decimals.
+///
+/// Arithmetic is checked and returns `None` on overflow or division by zero; intermediate
+/// results round down unless a `_rounded` variant picks the direction, the final conversion
+/// to an amount picks its `Rounding`.
+/// Stored in accounts as the raw scaled `u128`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, BorshSerialize, BorshDeserialize)]
+pub struct Decimal(u128);
+
+impl Decimal {
+    pub const fn zero() -> Self {
+        Decimal(0)
+    }
+
+    pub const fn one() -> Self {
+        Decimal(WAD)
+    }
+
+    /// Decimal from its raw value scaled by `WAD`.
+    pub const fn from_raw(raw: u128) -> Self {
+        Decimal(raw)
+    }
+
+    /// Raw value scaled by `WAD`.
+    pub const fn raw(self) -> u128 {
+        self.0
+    }
+
+    /// Decimal from a whole amount.
+    pub fn from_amount(amount: u64) -> Self {
+        // u64::MAX * WAD < u128::MAX
+        Decimal(amount as u128 * WAD)
+    }
+
+    /// `numerator / denominator`, rounded down.
+    pub fn from_ratio(numerator: u64, denominator: u64) -> Option<Self> {
+        Self::from_amount(numerator).checked_div(Self::from_amount(denominator))
+    }
+
+    /// Ratio expressed in basis points.
+    pub fn from_bps(bps: u64) -> Option<Self> {
+        Self::from_ratio(bps, BPS)
+    }
+
+    /// Value stored as an integer scaled by `scale`, e.g. the 1e9 `ACCURACY` of the bond programs.
+    pub fn from_scaled(value: u64, scale: u64) -> Option<Self> {
+        Self::from_ratio(value, scale)
+    }
+
+    /// 10^decimals, the number of base units in a whole token.
+    pub fn pow10(decimals: u8) -> Option<Self> {
+        10u128
+            .checked_pow(decimals as u32)
+            .and_then(|units| units.checked_mul(WAD))
+            .map(Decimal)
+    }
+
+    /// Converts a price per whole token to a price per base unit, from and to mints of the given decimals.
+    ///
+    /// A price of `quote` tokens per `base` token becomes a price of `quote` base units per `base` base unit.
+    pub fn to_base_units(self, base_decimals: u8, quote_decimals: u8, rounding: Rounding) -> Option<Self> {
+        self.checked_mul(Self::pow10(quote_decimals)?)?
+            .checked_div_rounded(Self::pow10(base_decimals)?, rounding)
+    }
+
+    pub fn checked_add(self, rhs: Self) -> Option<Self> {
+        self.0.checked_add(rhs.0).map(Decimal)
+    }
+
+    pub fn checked_sub(self, rhs: Self) -> Option<Self> {
+        self.0.checked_sub(rhs.0).map(Decimal)
+    }
+
+    /// `self * rhs`, rounded down.
+    pub fn checked_mul(self, rhs: Self) -> Option<Self> {
+        self.checked_mul_rounded(rhs, Rounding::Down)
+    }
+
+    /// `self * rhs`, rounded in the given direction.
+    pub fn checked_mul_rounded(self, rhs: Self, rounding: Rounding) -> Option<Self> {
+        let product = U192::from(self.0).checked_mul(U192::from(rhs.0))?;
+        Self::from_u192(div_u192(product, U192::from(WAD), rounding))
+    }
+
+    /// `self / rhs`, rounded down.
+    pub fn checked_div(self, rhs: Self) -> Option<Self> {
+        self.checked_div_rounded(rhs, Rounding::Down)
+    }
+
+    /// `self / rhs`, rounded in the given direction.
+    pub fn checked_div_rounded(self, rhs: Self, rounding: Rounding) -> Option<Self> {
+        if rhs.0 == 0 {
+            return None;
+        }
+        // self * WAD < 2^128 * 2^60 fits 192 bits
+        let quotient = div_u192(U192::from(self.0) * U192::from(WAD), U192::from(rhs.0), rounding);
+        Self::from_u192(quotient)
+    }
+
+    /// `self ^ exponent` by squaring, rounding each product down.
+    pub fn checked_pow(self, mut exponent: u64) -> Option<Self> {
+        let mut base = self;
+        let mut result = Self::one();
+        while exponent > 0 {
+            if exponent & 1 == 1 {
+                result = result.checked_mul(base)?;
+            }
+            exponent >>= 1;
+            if exponent > 0 {
+                base = base.checked_mul(base)?;
+            }
+        }
+        Some(result)
+    }
+
+    /// `e ^ self` by its Taylor series, rounding each term down; meant for small values.
+    pub fn checked_exp(self) -> Option<Self> {
+        let mut term = Self::one();
+        let mut sum = Self::one();
+        let mut n = 1;
+        while term != Self::zero() {
+            term = term.checked_mul(self)?.checked_div(Self::from_amount(n))?;
+            sum = sum.checked_add(term)?;
+            n += 1;
+        }
+        Some(sum)
+    }
+
+    /// Integer part, rounded in the given direction.
+    // `is_multiple_of` is not available on the BPF toolchain
+    #[allow(clippy::manual_is_multiple_of)]
+    pub fn to_u128(self, rounding: Rounding) -> u128 {
+        let whole = self.0 / WAD;
+        match rounding {
+            Rounding::Up if self.0 % WAD != 0 => whole + 1,
+            _ => whole,
+        }
+    }
+
+    /// Amount, rounded in the given direction; `None` when it does not fit a u64.
+    pub fn to_u64(self, rounding: Rounding) -> Option<u64> {
+        u64::try_from(self.to_u128(rounding)).ok()
+    }
+
+    /// Integer scaled by `scale`, rounded in the given direction; the inverse of `from_scaled`.
+    pub fn to_scaled(self, scale: u64, rounding: Rounding) -> Option<u64> {
+        self.checked_mul(Self::from_amount(scale))?.to_u64(rounding)
+    }
+
+    /// Basis points, rounded in the given direction.
+    pub fn to_bps(self, rounding: Rounding) -> Option<u64> {
+        self.to_scaled(BPS, rounding)
+    }
+
+    /// `amount * self` as an amount, rounded in the given direction.
+    pub fn mul_amount(self, amount: u64, rounding: Rounding) -> Option<u64> {
+        Self::from_amount(amount).checked_mul(self)?.to_u64(rounding)
+    }
+
+    /// `amount / self` as an amount, rounded in the given direction.
+    pub fn div_amount(self, amount: u64, rounding: Rounding) -> Option<u64> {
+        Self::from_amount(amount).checked_div(self)?.to_u64(rounding)
+    }
+
+    /// `amount / self` in base units, for `self` a price of `quote` tokens per `base` token and
+    /// `amount` in `quote` base units; rounded once, in the given direction.
+    pub fn div_amount_in_base_units(
+        self,
+        amount: u64,
+        base_decimals: u8,
+        quote_decimals: u8,
+        rounding: Rounding,
+    ) -> Option<u64> {
+        if self.0 == 0 {
+            return None;
+        }
+        let base_units = U192::from(10u128.checked_pow(base_decimals as u32)?);
+        let quote_units = U192::from(10u128.checked_pow(quote_decimals as u32)?);
+        let numerator = U192::from(amount)
+            .checked_mul(base_units)?
+            .checked_mul(U192::from(WAD))?;
+        let denominator = U192::from(self.0).checked_mul(quote_units)?;
+        let quotient = div_u192(numerator, denominator, rounding);
+        if quotient > U192::from(u64::MAX) {
+            None
+        } else {
+            Some(quotient.as_u64())
+        }
+    }
+
+    fn from_u192(value: U192) -> Option<Self> {
+        if value > U192::from(u128::MAX) {
+            None
+        } else {
+            Some(Decimal(value.as_u128()))
+        }
+    }
+}
+
+fn div_u192(numerator: U192, denominator: U192, rounding: Rounding) -> U192 {
+    let quotient = numerator / denominator;
+    match rounding {
+        Rounding::Up if !(numerator % denominator).is_zero() => quotient + 1,
+        _ => quotient,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn from_ratio() {
+        assert_eq!(Decimal::from_ratio(1, 2), Some(Decimal::from_raw(WAD / 2)));
+        assert_eq!(Decimal::from_ratio(1, 3), Some(Decimal::from_raw(333_333_333_333_333_333)));
+        assert_eq!(Decimal::from_ratio(5, 1), Some(Decimal::from_amount(5)));
+        assert_eq!(Decimal::from_ratio(1, 0), None);
+        assert_eq!(Decimal::from_bps(2_500), Decimal::from_ratio(1, 4));
+        assert_eq!(Decimal::from_scaled(500_000_000, 1_000_000_000), Decimal::from_ratio(1, 2));
+    }
+
+    #[test]
+    fn checked_mul() {
+        let half = Decimal::from_ratio(1, 2).unwrap();
+        assert_eq!(Decimal::from_amount(3).checked_mul(half), Some(Decimal::from_raw(3 * WAD / 2)));
+        // 1e-18 * 0.5 rounds down to zero
+        assert_eq!(Decimal::from_raw(1).checked_mul(half), Some(Decimal::zero()));
+        assert_eq!(Decimal::from_raw(u128::MAX).checked_mul(Decimal::one()), Some(Decimal::from_raw(u128::MAX)));
+        assert_eq!(Decimal::from_raw(u128::MAX).checked_mul(Decimal::from_amount(2)), None);
+        assert_eq!(Decimal::from_raw(1).checked_mul_rounded(half, Rounding::Up), Some(Decimal::from_raw(1)));
+        assert_eq!(Decimal::from_amount(3).checked_mul_rounded(half, Rounding::Up), Some(Decimal::from_raw(3 * WAD / 2)));
+    }
+
+    #[test]
+    fn checked_div() {
+        let third = Decimal::one().checked_div(Decimal::from_amount(3)).unwrap();
+        assert_eq!(third, Decimal::from_raw(333_333_333_333_333_333));
+        assert_eq!(Decimal::from_amount(6).checked_div(Decimal::from_amount(4)), Decimal::from_ratio(3, 2));
+        assert_eq!(Decimal::one().checked_div(Decimal::zero()), None);
+        assert_eq!(Decimal::from_raw(u128::MAX).checked_div(Decimal::from_ratio(1, 2).unwrap()), None);
+        assert_eq!(
+            Decimal::one().checked_div_rounded(Decimal::from_amount(3), Rounding::Up),
+            Some(Decimal::from_raw(333_333_333_333_333_334))
+        );
+        assert_eq!(Decimal::one().checked_div_rounded(Decimal::zero(), Rounding::Up), None);
+    }
+
+    #[test]
+    fn checked_add_sub() {
+        assert_eq!(Decimal::one().checked_add(Decimal::one()), Some(Decimal::from_amount(2)));
+        assert_eq!(Decimal::from_raw(u128::MAX).checked_add(Decimal::from_raw(1)), None);
+        assert_eq!(Decimal::zero().checked_sub(Decimal::from_raw(1)), None);
+    }
+
+    #[test]
+    fn checked_pow() {
+        let half = Decimal::from_ratio(1, 2).unwrap();
+        assert_eq!(half.checked_pow(0), Some(Decimal::one()));
+        assert_eq!(half.checked_pow(3), Decimal::from_ratio(1, 8));
+        assert_eq!(Decimal::from_amount(10).checked_pow(18), Decimal::pow10(18));
+        assert_eq!(Decimal::from_amount(10).checked_pow(21), None);
+    }
+
+    #[test]
+    fn rounding() {
+        let value = Decimal::from_ratio(7, 2).unwrap();
+        assert_eq!(value.to_u64(Rounding::Down), Some(3));
+        assert_eq!(value.to_u64(Rounding::Up), Some(4));
+
+        let whole = Decimal::from_amount(3);
+        assert_eq!(whole.to_u64(Rounding::Down), Some(3));
+        assert_eq!(whole.to_u64(Rounding::Up), Some(3));
+
+        let third = Decimal::from_ratio(1, 3).unwrap();
+        assert_eq!(third.mul_amount(10, Rounding::Down), Some(3));
+        assert_eq!(third.mul_amount(10, Rounding::Up), Some(4));
+        assert_eq!(third.div_amount(1, Rounding::Down), Some(3));
+        assert_eq!(third.div_amount(1, Rounding::Up), Some(4));
+        assert_eq!(third.to_bps(Rounding::Down), Some(3_333));
+        assert_eq!(third.to_bps(Rounding::Up), Some(3_334));
+    }
+
+    #[test]
+    fn to_u64_overflow() {
+        assert_eq!(Decimal::from_amount(u64::MAX).to_u64(Rounding::Down), Some(u64::MAX));
+        assert_eq!(Decimal::from_amount(u64::MAX).checked_add(Decimal::from_raw(1)).unwrap().to_u64(Rounding::Up), None);
+    }
+
+    #[test]
+    fn to_base_units() {
+        // 2 quote tokens of 6 decimals per base token of 9 decimals
+        let price = Decimal::from_amount(2).to_base_units(9, 6, Rounding::Down).unwrap();
+        assert_eq!(price, Decimal::from_ratio(2, 1_000).unwrap());
+        assert_eq!(price.mul_amount(1_000_000_000, Rounding::Down), Some(2_000_000));
+
+        // 1e-18 per whole token of 9 decimals truncates to zero per base unit unless rounded up
+        let tiny = Decimal::from_raw(1);
+        assert_eq!(tiny.to_base_units(9, 0, Rounding::Down), Some(Decimal::zero()));
+        assert_eq!(tiny.to_base_units(9, 0, Rounding::Up), Some(Decimal::from_raw(1)));
+    }
+
+    #[test]
+    fn div_amount_in_base_units() {
+        // 2 quote tokens of 6 decimals per base token of 9 decimals
+        let price = Decimal::from_amount(2);
+        assert_eq!(price.div_amount_in_base_units(2_000_000, 9, 6, Rounding::Down), Some(1_000_000_000));
+
+        // 3e-9 quote base units per base token of 10 decimals is below 1e-18 per base unit,
+        // the amount keeps its precision instead of rounding the price
+        let tiny = Decimal::from_scaled(3, 1_000_000_000).unwrap();
+        assert_eq!(tiny.div_amount_in_base_units(1, 10, 0, Rounding::Down), Some(3_333_333_333_333_333_333));
+        assert_eq!(tiny.div_amount_in_base_units(1, 10, 0, Rounding::Up), Some(3_333_333_333_333_333_334));
+
+        assert_eq!(Decimal::zero().div_amount_in_base_units(1, 9, 9, Rounding::Down), None);
+        assert_eq!(tiny.div_amount_in_base_units(u64::MAX, 9, 0, Rounding::Down), None);
+    }
+}
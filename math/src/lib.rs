@@ -0,0 +1,6 @@
+//! Deterministic fixed-point math shared by the on-chain programs and their clients.
+
+/// decimal
+pub mod decimal;
+
+pub use decimal::*;
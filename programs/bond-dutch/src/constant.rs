@@ -4,5 +4,5 @@ pub const PROJECT_BONDS_SEED: &'static [u8] = b"project-bonds";
 pub const BONDS_INFO_SEED: &'static [u8] = b"bonds-info";
 pub const TOKEN_VAULT_SEED: &'static [u8] = b"token-vault";
 
-/// General decimal values ACCURACY unless specified differently (e.g. fees, exchange rates)
+/// Scale of the rates and prices stored in accounts, read with `Decimal::from_scaled`
 pub const ACCURACY: u64 = 1000000000;
@@ -1,7 +1,8 @@
 use anchor_lang::prelude::*;
 use anchor_spl::token::{Mint, TokenAccount, Transfer, Token};
+use bonding_math::{Decimal, Rounding};
 
-use crate::{constant::*, states::*};
+use crate::{constant::*, errors::BondError, states::*};
 
 #[derive(Accounts)]
 pub struct InitAuction<'info> {
@@ -34,7 +35,7 @@ pub struct InitAuction<'info> {
         seeds = [token_mint.key().as_ref(), PROJECT_INFO_SEED.as_ref(), project_auctions.next_auction_id.to_string().as_bytes()],
         bump,
         payer = initializer,
-        space = 8 + 32 + 32 + 32 + 32 + 8 + 8 * 3 + 8 * 6 + 8 * 2 + 1024 // 1024 gap
+        space = 8 + 32 + 32 + 32 + 32 + 8 + 8 * 3 + 8 * 6 + 8 * 2 + 2 + 1024 // 1024 gap
     )]
     pub auction_info: Box<Account<'info, AuctionInfo>>,
     pub rent: Sysvar<'info, Rent>,
@@ -67,7 +68,7 @@ pub struct UpdateAuthority<'info> {
     #[account(
         mut,
         seeds = [auction_info.project_token.as_ref(), PROJECT_INFO_SEED.as_ref(), auction_id.to_string().as_bytes()],
-        bump,
+        bump = auction_info.bump,
     )]
     pub auction_info: Box<Account<'info, AuctionInfo>>,
 }
@@ -83,7 +84,7 @@ pub struct UpdateSettings<'info> {
     #[account(
         mut,
         seeds = [auction_info.project_token.as_ref(), PROJECT_INFO_SEED.as_ref(), auction_id.to_string().as_bytes()],
-        bump,
+        bump = auction_info.bump,
     )]
     pub auction_info: Box<Account<'info, AuctionInfo>>,
 }
@@ -106,14 +107,14 @@ pub struct Bond<'info> {
     #[account(
         mut,
         seeds = [token_mint.key().as_ref(), PROJECT_INFO_SEED.as_ref(), auction_id.to_string().as_bytes()],
-        bump,
+        bump = auction_info.bump,
         constraint = auction_info.auction_start_time <= clock.unix_timestamp as u64,
         constraint = auction_info.auction_end_time >= clock.unix_timestamp as u64
     )]
     pub auction_info: Box<Account<'info, AuctionInfo>>,
     #[account(
-        init,
-        seeds = [token_mint.key().as_ref(), user.key().as_ref(), VESTING_INFO_SEED.as_ref()],
+        init_if_needed,
+        seeds = [token_mint.key().as_ref(), user.key().as_ref(), VESTING_INFO_SEED.as_ref(), auction_id.to_string().as_bytes()],
         bump,
         payer = user,
         space = 256 // 32 is enough for now
@@ -148,7 +149,7 @@ pub struct EndAuction<'info> {
     #[account(
         mut,
         seeds = [auction_info.project_token.as_ref(), PROJECT_INFO_SEED.as_ref(), auction_id.to_string().as_bytes()],
-        bump,
+        bump = auction_info.bump,
         constraint = auction_info.auction_end_time < clock.unix_timestamp as u64
     )]
     pub auction_info: Box<Account<'info, AuctionInfo>>,
@@ -167,8 +168,8 @@ pub struct WithdrawFunds<'info> {
     #[account(
         mut,
         seeds = [auction_info.project_token.as_ref(), PROJECT_INFO_SEED.as_ref(), auction_id.to_string().as_bytes()],
-        bump,
-        constraint = auction_info.is_auction_success == false,
+        bump = auction_info.bump,
+        constraint = !auction_info.is_auction_success,
         constraint = auction_info.auction_end_time < clock.unix_timestamp as u64
     )]
     pub auction_info: Box<Account<'info, AuctionInfo>>,
@@ -193,28 +194,36 @@ impl<'info> Bond<'info> {
 
 
 #[derive(Accounts)]
-#[instruction(auction_id: u64, bond_id: u64)]
+#[instruction(auction_id: u64)]
 pub struct WithdrawVesting<'info> {
     #[account(mut)]
     pub taker: Signer<'info>,
-    #[account(mut)]
+    /// CHECK: owner of the position, only used to derive the vesting info address
+    pub owner: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        constraint = taker_receive_token_account.mint == auction_info.project_token @ BondError::InvalidReceiveTokenAccount,
+    )]
     pub taker_receive_token_account: Account<'info, TokenAccount>,
     #[account(
         mut,
         seeds = [auction_info.project_token.as_ref(), TOKEN_VAULT_SEED.as_ref(), auction_id.to_string().as_bytes()],
-        bump,
+        bump = auction_info.vault_bump,
     )]
     pub vault_account: Box<Account<'info, TokenAccount>>,
 
     #[account(
         mut,
         seeds = [auction_info.project_token.as_ref(), PROJECT_INFO_SEED.as_ref(), auction_id.to_string().as_bytes()],
-        bump,
+        bump = auction_info.bump,
+        constraint = auction_info.is_auction_success @ BondError::AuctionNotSucceeded,
     )]
     pub auction_info: Box<Account<'info, AuctionInfo>>,
     #[account(
-        seeds = [auction_info.project_token.as_ref(), taker.key().as_ref(), VESTING_INFO_SEED.as_ref(), bond_id.to_string().as_bytes()],
-        bump,
+        mut,
+        seeds = [auction_info.project_token.as_ref(), owner.key().as_ref(), VESTING_INFO_SEED.as_ref(), auction_id.to_string().as_bytes()],
+        bump = vesting_info.bump,
+        constraint = vesting_info.can_claim(owner.key, taker.key, &taker_receive_token_account.owner) @ BondError::NotVestingOwner,
     )]
     pub vesting_info: Box<Account<'info, VestingInfo>>,
     pub token_program: Program<'info, Token>,
@@ -236,14 +245,15 @@ impl<'info> WithdrawVesting<'info> {
 
 
     pub fn taker_vested_amount(&self, accuracy: u64) -> u64 {
-        let lock_end_time = self.vesting_info.start_time + self.auction_info.lock_period;
+        let total_amount = self.auction_info.allocation(self.vesting_info.bonded_lp_amount);
+        let lock_end_time = self.auction_info.vesting_start_time + self.auction_info.lock_period;
         let vesting_end_time = lock_end_time + self.auction_info.vesting_period;
         let vesting_info = &self.vesting_info;
 
         let vesting_unlock_amount =
-            vesting_info.total_amount * self.auction_info.instant_unlock / accuracy;
+            scaled_share(total_amount, self.auction_info.instant_unlock, accuracy);
 
-        if self.vesting_info.start_time == 0 || vesting_info.total_amount == 0 {
+        if self.auction_info.vesting_start_time == 0 || total_amount == 0 {
             return 0;
         }
 
@@ -252,28 +262,83 @@ impl<'info> WithdrawVesting<'info> {
         }
 
         if self.clock.unix_timestamp as u64 > vesting_end_time {
-            return vesting_info.total_amount;
+            return total_amount;
         }
 
-        let initial_unlock_amount =
-            vesting_info.total_amount * self.auction_info.initial_unlock / accuracy;
-        let unlock_amount_per_interval =
-            vesting_info.total_amount * self.auction_info.release_rate / accuracy;
-        let mut vested_amount = (self.clock.unix_timestamp as u64 - lock_end_time)
-            / self.auction_info.release_interval
-            * unlock_amount_per_interval
-            + initial_unlock_amount
-            + vesting_unlock_amount;
+        // the unlocked rates are summed before converting to an amount so that
+        // truncation happens once instead of once per interval
+        let released_rate = ((self.clock.unix_timestamp as u64 - lock_end_time) / self.auction_info.release_interval)
+            .saturating_mul(self.auction_info.release_rate);
+        let unlocked_rate = self.auction_info.instant_unlock
+            .saturating_add(self.auction_info.initial_unlock)
+            .saturating_add(released_rate)
+            .min(accuracy);
+        let mut vested_amount = scaled_share(total_amount, unlocked_rate, accuracy);
         let withdrawn_amount = vesting_info.withdrawn_amount;
 
         if withdrawn_amount > vested_amount {
             vested_amount = withdrawn_amount;
         }
 
-        if vested_amount > vesting_info.total_amount {
-            vested_amount = vesting_info.total_amount;
+        if vested_amount > total_amount {
+            vested_amount = total_amount;
         }
         return vested_amount;
     }
 }
 
+#[derive(Accounts)]
+#[instruction(auction_id: u64)]
+pub struct SetClaimDelegate<'info> {
+    pub owner: Signer<'info>,
+    #[account(
+        seeds = [auction_info.project_token.as_ref(), PROJECT_INFO_SEED.as_ref(), auction_id.to_string().as_bytes()],
+        bump = auction_info.bump,
+    )]
+    pub auction_info: Box<Account<'info, AuctionInfo>>,
+    #[account(
+        mut,
+        seeds = [auction_info.project_token.as_ref(), owner.key().as_ref(), VESTING_INFO_SEED.as_ref(), auction_id.to_string().as_bytes()],
+        bump = vesting_info.bump,
+    )]
+    pub vesting_info: Box<Account<'info, VestingInfo>>,
+}
+
+#[derive(Accounts)]
+#[instruction(auction_id: u64)]
+pub struct MigrateVesting<'info> {
+    /// Project owner, vouching that the legacy position was bonded in this auction
+    #[account(
+        constraint = *user.key == auction_info.project_owner,
+    )]
+    pub user: Signer<'info>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    #[account(
+        seeds = [auction_info.project_token.as_ref(), PROJECT_INFO_SEED.as_ref(), auction_id.to_string().as_bytes()],
+        bump = auction_info.bump,
+    )]
+    pub auction_info: Box<Account<'info, AuctionInfo>>,
+    #[account(
+        mut,
+        seeds = [auction_info.project_token.as_ref(), owner.key().as_ref(), VESTING_INFO_SEED.as_ref()],
+        bump,
+        close = owner,
+    )]
+    pub legacy_vesting_info: Box<Account<'info, VestingInfo>>,
+    #[account(
+        init,
+        seeds = [auction_info.project_token.as_ref(), owner.key().as_ref(), VESTING_INFO_SEED.as_ref(), auction_id.to_string().as_bytes()],
+        bump,
+        payer = owner,
+        space = 256 // 32 is enough for now
+    )]
+    pub vesting_info: Box<Account<'info, VestingInfo>>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Share of `amount` for a rate scaled by `accuracy`, rounded down as it is paid out.
+fn scaled_share(amount: u64, rate: u64, accuracy: u64) -> u64 {
+    Decimal::from_scaled(rate, accuracy).unwrap()
+        .mul_amount(amount, Rounding::Down).unwrap()
+}
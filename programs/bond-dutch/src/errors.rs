@@ -0,0 +1,16 @@
+use anchor_lang::prelude::*;
+
+#[error_code]
+pub enum BondError {
+    #[msg("The signer is neither the owner nor the claim delegate of the vesting position")]
+    NotVestingOwner,
+
+    #[msg("The receiving token account does not hold the project token")]
+    InvalidReceiveTokenAccount,
+
+    #[msg("The auction has not ended successfully")]
+    AuctionNotSucceeded,
+
+    #[msg("The auction has no tokens to price or its final price is out of range")]
+    InvalidFinalPrice,
+}
@@ -4,6 +4,8 @@ use anchor_lang::prelude::*;
 pub mod constant;
 /// context accounts
 pub mod context_accounts;
+/// errors
+pub mod errors;
 /// processor
 pub mod processor;
 /// states
@@ -29,6 +31,7 @@ pub mod bond {
         process_update_settings(ctx, auction_settings, vesting_schedule)
     }
 
+    /// Adds `lp_amount` lp tokens to the signer's position in the auction, created on its first bond.
     pub fn bond(ctx: Context<Bond>, _auction_id: u64, lp_amount: u64) -> Result<()> {
         process_bond(ctx, lp_amount)
     }
@@ -37,7 +40,18 @@ pub mod bond {
         process_end_auction(ctx)
     }
 
-    pub fn withdraw_vesting(ctx: Context<WithdrawVesting>, _auction_id: u64, _bond_id: u64) -> Result<()> {
-        process_withdraw_vesting(ctx)
+    /// Withdraws the vested part of the owner's pro rata allocation, once the auction ended successfully.
+    pub fn withdraw_vesting(ctx: Context<WithdrawVesting>, auction_id: u64) -> Result<()> {
+        process_withdraw_vesting(ctx, auction_id)
+    }
+
+    pub fn set_claim_delegate(ctx: Context<SetClaimDelegate>, _auction_id: u64, delegate: Option<Pubkey>) -> Result<()> {
+        process_set_claim_delegate(ctx, delegate)
+    }
+
+    /// Moves a position bonded before positions were seeded by auction id into the auction's position.
+    /// Signed by the project owner, vouching for the auction, and by the owner of the position.
+    pub fn migrate_vesting(ctx: Context<MigrateVesting>, _auction_id: u64) -> Result<()> {
+        process_migrate_vesting(ctx)
     }
 }
@@ -4,10 +4,14 @@ pub mod process_bond;
 pub mod process_withdraw_vesting;
 pub mod process_update_settings;
 pub mod process_end_auction;
+pub mod process_set_claim_delegate;
+pub mod process_migrate_vesting;
 
 pub use process_init_auction::*;
 pub use process_update_authority::*;
 pub use process_bond::*;
 pub use process_withdraw_vesting::*;
 pub use process_update_settings::*;
-pub use process_end_auction::*;
\ No newline at end of file
+pub use process_end_auction::*;
+pub use process_set_claim_delegate::*;
+pub use process_migrate_vesting::*;
\ No newline at end of file
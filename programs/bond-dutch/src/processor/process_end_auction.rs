@@ -1,11 +1,15 @@
 use anchor_lang::prelude::*;
+use bonding_math::{Decimal, Rounding};
 
-use crate::{context_accounts::*, constant};
+use crate::{context_accounts::*, constant, errors::BondError};
 
 pub fn process_end_auction(
     ctx: Context<EndAuction>
 ) -> Result<()> {
-    let final_price = ctx.accounts.auction_info.bonded_lp_amount * constant::ACCURACY / ctx.accounts.auction_info.token_amount;
+    // no final price for an auction of zero tokens
+    let final_price = Decimal::from_ratio(ctx.accounts.auction_info.bonded_lp_amount, ctx.accounts.auction_info.token_amount)
+        .and_then(|price| price.to_scaled(constant::ACCURACY, Rounding::Down))
+        .ok_or(BondError::InvalidFinalPrice)?;
     ctx.accounts.auction_info.is_auction_success = final_price >= ctx.accounts.auction_info.min_price;
     ctx.accounts.auction_info.final_price = final_price;
     Ok(())
@@ -30,6 +30,9 @@ pub fn process_init_auction(
     ctx.accounts.auction_info.vesting_period = vesting_schedule.vesting_period;
     ctx.accounts.auction_info.vesting_start_time = vesting_schedule.start_time;
 
+    ctx.accounts.auction_info.bump = *ctx.bumps.get("auction_info").unwrap();
+    ctx.accounts.auction_info.vault_bump = *ctx.bumps.get("vault_account").unwrap();
+
     ctx.accounts.project_auctions.next_auction_id = ctx.accounts.project_auctions.next_auction_id + 1;
 
     token::transfer(ctx.accounts.into_deposit_to_vault_context(), amount)?;
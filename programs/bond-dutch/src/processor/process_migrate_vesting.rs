@@ -0,0 +1,14 @@
+use anchor_lang::prelude::*;
+
+use crate::context_accounts::*;
+
+/// Copies a legacy position into the auction's position; the legacy account is closed to its owner.
+pub fn process_migrate_vesting(
+    ctx: Context<MigrateVesting>
+) -> Result<()> {
+    ctx.accounts.vesting_info.bonded_lp_amount = ctx.accounts.legacy_vesting_info.bonded_lp_amount;
+    ctx.accounts.vesting_info.withdrawn_amount = ctx.accounts.legacy_vesting_info.withdrawn_amount;
+    ctx.accounts.vesting_info.claim_delegate = ctx.accounts.legacy_vesting_info.claim_delegate;
+    ctx.accounts.vesting_info.bump = *ctx.bumps.get("vesting_info").unwrap();
+    Ok(())
+}
@@ -0,0 +1,11 @@
+use anchor_lang::prelude::*;
+
+use crate::context_accounts::*;
+
+pub fn process_set_claim_delegate(
+    ctx: Context<SetClaimDelegate>,
+    delegate: Option<Pubkey>
+) -> Result<()> {
+    ctx.accounts.vesting_info.claim_delegate = delegate.unwrap_or_default();
+    Ok(())
+}
@@ -1,4 +1,5 @@
 use anchor_lang::prelude::*;
+use bonding_math::{Decimal, Rounding};
 
 #[derive(Default, Clone, AnchorSerialize, AnchorDeserialize)]
 pub struct VestingSchedule {
@@ -84,7 +85,23 @@ pub struct AuctionInfo {
     /// Final price
     pub final_price: u64,
     /// Is success
-    pub is_auction_success: bool
+    pub is_auction_success: bool,
+
+    /*************************** Bumps *************************/
+
+    /// Canonical bump of the auction info
+    pub bump: u8,
+    /// Canonical bump of the token vault, used to sign transfers out of it
+    pub vault_bump: u8,
+}
+
+impl AuctionInfo {
+    /// Project tokens allocated to `bonded_lp_amount` lp tokens, pro rata of all bonded lp tokens.
+    pub fn allocation(&self, bonded_lp_amount: u64) -> u64 {
+        Decimal::from_ratio(bonded_lp_amount, self.bonded_lp_amount)
+            .and_then(|share| share.mul_amount(self.token_amount, Rounding::Down))
+            .unwrap_or(0)
+    }
 }
 
 #[account]
@@ -93,6 +110,9 @@ pub struct ProjectAuctions {
     pub next_auction_id: u64
 }
 
+/// Position of a wallet in an auction, at `[project token, owner, VESTING_INFO_SEED, auction id]`,
+/// accumulating all its bonds in the auction. Positions bonded before the auction id was part of the
+/// seeds live at `[project token, owner, VESTING_INFO_SEED]` and are moved here by `migrate_vesting`.
 #[account]
 #[derive(Default)]
 pub struct VestingInfo {
@@ -100,4 +120,19 @@ pub struct VestingInfo {
     pub bonded_lp_amount: u64,
     /// The amount that has been withdrawn.
     pub withdrawn_amount: u64,
+    /// Wallet allowed to claim the position into token accounts of the owner
+    pub claim_delegate: Pubkey,
+    /// Canonical bump of the vesting info
+    pub bump: u8,
+}
+
+impl VestingInfo {
+    /// Whether `signer` may claim the position of `owner` into a token account owned by `recipient`.
+    /// The owner may claim anywhere, the claim delegate only into accounts of the owner.
+    pub fn can_claim(&self, owner: &Pubkey, signer: &Pubkey, recipient: &Pubkey) -> bool {
+        if signer == owner {
+            return true;
+        }
+        self.claim_delegate != Pubkey::default() && *signer == self.claim_delegate && recipient == owner
+    }
 }
@@ -3,6 +3,14 @@ pub const VESTING_INFO_SEED: &'static [u8] = b"vesting-info";
 pub const PROJECT_BONDS_SEED: &'static [u8] = b"project-bonds";
 pub const BONDS_INFO_SEED: &'static [u8] = b"bonds-info";
 pub const TOKEN_VAULT_SEED: &'static [u8] = b"token-vault";
+pub const MILESTONE_TABLE_SEED: &'static [u8] = b"milestone-table";
+pub const POSITION_MINT_SEED: &'static [u8] = b"position-mint";
+pub const POSITION_INFO_SEED: &'static [u8] = b"position-info";
 
-/// General decimal values ACCURACY unless specified differently (e.g. fees, exchange rates)
+/// Scale of the rates and prices stored in accounts, read with `Decimal::from_scaled`
 pub const ACCURACY: u64 = 1000000000;
+
+/// Denominator for values expressed in bips
+pub const BIPS: u64 = 10000;
+/// Maximum number of entries in a milestone table
+pub const MAX_MILESTONES: usize = 32;
@@ -1,7 +1,8 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::{Mint, TokenAccount, Transfer, Token};
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token::{Mint, MintTo, SetAuthority, TokenAccount, Transfer, Token};
 
-use crate::{constant::*, states::*};
+use crate::{constant::*, errors::BondError, states::*};
 
 #[derive(Accounts)]
 pub struct InitNewProject<'info> {
@@ -34,7 +35,7 @@ pub struct InitNewProject<'info> {
         seeds = [token_mint.key().as_ref(), PROJECT_INFO_SEED.as_ref(), project_bonds.next_bonding_id.to_string().as_bytes()],
         bump,
         payer = initializer,
-        space = 8 + 32 + 32 + 32 + 32 + 8 + 8 * 3 + 8 * 6 + 8 * 2 + 1024 // 1024 gap
+        space = 8 + 32 + 32 + 32 + 32 + 8 + 8 * 3 + 8 * 6 + 8 * 2 + 32 + 8 + 2 + 1024 // 1024 gap
     )]
     pub project_info: Box<Account<'info, ProjectInfo>>,
     pub rent: Sysvar<'info, Rent>,
@@ -67,7 +68,7 @@ pub struct UpdateAuthority<'info> {
     #[account(
         mut,
         seeds = [project_info.project_token.as_ref(), PROJECT_INFO_SEED.as_ref(), project_bonding_id.to_string().as_bytes()],
-        bump,
+        bump = project_info.bump,
     )]
     pub project_info: Box<Account<'info, ProjectInfo>>,
 }
@@ -83,9 +84,35 @@ pub struct UpdatePrice<'info> {
     #[account(
         mut,
         seeds = [project_info.project_token.as_ref(), PROJECT_INFO_SEED.as_ref(), project_bonding_id.to_string().as_bytes()],
-        bump,
+        bump = project_info.bump,
+    )]
+    pub project_info: Box<Account<'info, ProjectInfo>>,
+}
+
+#[derive(Accounts)]
+#[instruction(project_bonding_id: u64)]
+pub struct InitMilestoneTable<'info> {
+    #[account(
+        mut,
+        constraint = *user.key == project_info.project_owner,
+    )]
+    pub user: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [project_info.project_token.as_ref(), PROJECT_INFO_SEED.as_ref(), project_bonding_id.to_string().as_bytes()],
+        bump = project_info.bump,
+        constraint = project_info.vested_amount == 0 @ BondError::ProjectAlreadyBonded,
     )]
     pub project_info: Box<Account<'info, ProjectInfo>>,
+    #[account(
+        init,
+        seeds = [project_info.project_token.as_ref(), MILESTONE_TABLE_SEED.as_ref(), project_bonding_id.to_string().as_bytes()],
+        bump,
+        payer = user,
+        space = 8 + 32 + 8 + 4 + 16 * MAX_MILESTONES
+    )]
+    pub milestone_table: Box<Account<'info, MilestoneTable>>,
+    pub system_program: Program<'info, System>,
 }
 
 #[derive(Accounts)]
@@ -106,7 +133,7 @@ pub struct Bond<'info> {
     #[account(
         mut,
         seeds = [token_mint.key().as_ref(), PROJECT_INFO_SEED.as_ref(), project_bonding_id.to_string().as_bytes()],
-        bump,
+        bump = project_info.bump,
     )]
     pub project_info: Box<Account<'info, ProjectInfo>>,
     #[account(
@@ -145,30 +172,138 @@ impl<'info> Bond<'info> {
     }
 }
 
+#[derive(Accounts)]
+#[instruction(project_bonding_id: u64)]
+pub struct BondWithPositionMint<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+    pub lp_mint: Account<'info, Mint>,
+    #[account(mut)]
+    pub lp_deposit_account: Account<'info, TokenAccount>,
+    #[account(
+        mut,
+        constraint = project_info.lp_token_account == lp_recieve_account.key()
+    )]
+    pub lp_recieve_account: Account<'info, TokenAccount>,
+    pub token_mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [token_mint.key().as_ref(), PROJECT_INFO_SEED.as_ref(), project_bonding_id.to_string().as_bytes()],
+        bump = project_info.bump,
+    )]
+    pub project_info: Box<Account<'info, ProjectInfo>>,
+    #[account(
+        init_if_needed,
+        seeds = [token_mint.key().as_ref(), user.key().as_ref(), BONDS_INFO_SEED.as_ref()],
+        bump,
+        payer = user,
+        space = 256 // 16 is enough for now
+    )]
+    pub bonds_info: Box<Account<'info, BondsInfo>>,
+    #[account(
+        init,
+        seeds = [token_mint.key().as_ref(), user.key().as_ref(), VESTING_INFO_SEED.as_ref(), bonds_info.total_bonds.to_string().as_bytes()],
+        bump,
+        payer = user,
+        space = 256 // 32 is enough for now
+    )]
+    pub vesting_info: Box<Account<'info, VestingInfo>>,
+    #[account(
+        init,
+        seeds = [vesting_info.key().as_ref(), POSITION_MINT_SEED.as_ref()],
+        bump,
+        payer = user,
+        mint::decimals = 0,
+        mint::authority = position_mint,
+    )]
+    pub position_mint: Box<Account<'info, Mint>>,
+    #[account(
+        init,
+        seeds = [position_mint.key().as_ref(), POSITION_INFO_SEED.as_ref()],
+        bump,
+        payer = user,
+        space = 8 + 32 * 3 + 8 + 1
+    )]
+    pub position_info: Box<Account<'info, PositionInfo>>,
+    #[account(
+        init,
+        payer = user,
+        associated_token::mint = position_mint,
+        associated_token::authority = user,
+    )]
+    pub position_token_account: Box<Account<'info, TokenAccount>>,
+
+    pub system_program: Program<'info, System>,
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub rent: Sysvar<'info, Rent>,
+    pub clock: Sysvar<'info, Clock>
+}
+
+impl<'info> BondWithPositionMint<'info> {
+    pub fn into_bond_lp_to_project_context(&self) -> CpiContext<'_, '_, '_, 'info, Transfer<'info>> {
+        let cpi_accounts = Transfer {
+            from: self
+                .lp_deposit_account
+                .to_account_info()
+                .clone(),
+            to: self.lp_recieve_account.to_account_info().clone(),
+            authority: self.user.to_account_info().clone(),
+        };
+        CpiContext::new(self.token_program.to_account_info().clone(), cpi_accounts)
+    }
+
+    pub fn into_mint_position_context(&self) -> CpiContext<'_, '_, '_, 'info, MintTo<'info>> {
+        let cpi_accounts = MintTo {
+            mint: self.position_mint.to_account_info().clone(),
+            to: self.position_token_account.to_account_info().clone(),
+            authority: self.position_mint.to_account_info().clone(),
+        };
+        CpiContext::new(self.token_program.to_account_info().clone(), cpi_accounts)
+    }
+
+    pub fn into_lock_position_supply_context(&self) -> CpiContext<'_, '_, '_, 'info, SetAuthority<'info>> {
+        let cpi_accounts = SetAuthority {
+            account_or_mint: self.position_mint.to_account_info().clone(),
+            current_authority: self.position_mint.to_account_info().clone(),
+        };
+        CpiContext::new(self.token_program.to_account_info().clone(), cpi_accounts)
+    }
+}
 
 #[derive(Accounts)]
 #[instruction(project_bonding_id: u64, bond_id: u64)]
 pub struct WithdrawVesting<'info> {
     #[account(mut)]
     pub taker: Signer<'info>,
-    #[account(mut)]
+    /// CHECK: wallet that bonded the position, only used to derive the vesting info address
+    pub bonder: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        constraint = taker_receive_token_account.mint == project_info.project_token @ BondError::InvalidReceiveTokenAccount,
+    )]
     pub taker_receive_token_account: Account<'info, TokenAccount>,
     #[account(
         mut,
         seeds = [project_info.project_token.as_ref(), TOKEN_VAULT_SEED.as_ref(), project_bonding_id.to_string().as_bytes()],
-        bump,
+        bump = project_info.vault_bump,
     )]
     pub vault_account: Box<Account<'info, TokenAccount>>,
 
     #[account(
         mut,
         seeds = [project_info.project_token.as_ref(), PROJECT_INFO_SEED.as_ref(), project_bonding_id.to_string().as_bytes()],
-        bump,
+        bump = project_info.bump,
     )]
     pub project_info: Box<Account<'info, ProjectInfo>>,
     #[account(
-        seeds = [project_info.project_token.as_ref(), taker.key().as_ref(), VESTING_INFO_SEED.as_ref(), bond_id.to_string().as_bytes()],
-        bump,
+        mut,
+        seeds = [project_info.project_token.as_ref(), bonder.key().as_ref(), VESTING_INFO_SEED.as_ref(), bond_id.to_string().as_bytes()],
+        bump = vesting_info.bump,
+        constraint = vesting_info.can_claim(bonder.key, taker.key, &taker_receive_token_account.owner) @ BondError::NotVestingOwner,
+        constraint = vesting_info.project_info == project_info.key() @ BondError::WrongProject,
+        constraint = !vesting_info.is_tokenized() @ BondError::TokenizedPosition,
     )]
     pub vesting_info: Box<Account<'info, VestingInfo>>,
     pub token_program: Program<'info, Token>,
@@ -189,44 +324,416 @@ impl<'info> WithdrawVesting<'info> {
     }
 
 
-    pub fn taker_vested_amount(&self, accuracy: u64) -> u64 {
-        let lock_end_time = self.vesting_info.start_time + self.project_info.lock_period;
-        let vesting_end_time = lock_end_time + self.project_info.vesting_period;
-        let vesting_info = &self.vesting_info;
-
-        let vesting_unlock_amount =
-            vesting_info.total_amount * self.project_info.instant_unlock / accuracy;
-
-        if self.vesting_info.start_time == 0 || vesting_info.total_amount == 0 {
-            return 0;
-        }
-
-        if self.clock.unix_timestamp as u64 <= lock_end_time {
-            return vesting_unlock_amount;
-        }
-
-        if self.clock.unix_timestamp as u64 > vesting_end_time {
-            return vesting_info.total_amount;
-        }
-
-        let initial_unlock_amount =
-            vesting_info.total_amount * self.project_info.initial_unlock / accuracy;
-        let unlock_amount_per_interval =
-            vesting_info.total_amount * self.project_info.release_rate / accuracy;
-        let mut vested_amount = (self.clock.unix_timestamp as u64 - lock_end_time)
-            / self.project_info.release_interval
-            * unlock_amount_per_interval
-            + initial_unlock_amount
-            + vesting_unlock_amount;
-        let withdrawn_amount = vesting_info.withdrawn_amount;
-
-        if withdrawn_amount > vested_amount {
-            vested_amount = withdrawn_amount;
-        }
-
-        if vested_amount > vesting_info.total_amount {
-            vested_amount = vesting_info.total_amount;
-        }
-        return vested_amount;
+    pub fn taker_vested_amount(&self, accuracy: u64, milestone_table: Option<&MilestoneTable>) -> u64 {
+        self.vesting_info.vested_amount(
+            &self.project_info,
+            self.clock.unix_timestamp as u64,
+            accuracy,
+            milestone_table,
+        )
+    }
+}
+
+#[derive(Accounts)]
+#[instruction(project_bonding_id: u64)]
+pub struct WithdrawAllVesting<'info> {
+    #[account(mut)]
+    pub taker: Signer<'info>,
+    #[account(
+        mut,
+        constraint = taker_receive_token_account.mint == project_info.project_token @ BondError::InvalidReceiveTokenAccount,
+    )]
+    pub taker_receive_token_account: Account<'info, TokenAccount>,
+    #[account(
+        mut,
+        seeds = [project_info.project_token.as_ref(), TOKEN_VAULT_SEED.as_ref(), project_bonding_id.to_string().as_bytes()],
+        bump = project_info.vault_bump,
+    )]
+    pub vault_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        seeds = [project_info.project_token.as_ref(), PROJECT_INFO_SEED.as_ref(), project_bonding_id.to_string().as_bytes()],
+        bump = project_info.bump,
+    )]
+    pub project_info: Box<Account<'info, ProjectInfo>>,
+    pub token_program: Program<'info, Token>,
+    pub clock: Sysvar<'info, Clock>,
+}
+
+impl<'info> WithdrawAllVesting<'info> {
+    pub fn into_transfer_to_taker(&self) -> CpiContext<'_, '_, '_, 'info, Transfer<'info>> {
+        let cpi_accounts = Transfer {
+            from: self
+                .vault_account
+                .to_account_info()
+                .clone(),
+            to: self.taker_receive_token_account.to_account_info().clone(),
+            authority: self.vault_account.to_account_info().clone(),
+        };
+        CpiContext::new(self.token_program.to_account_info().clone(), cpi_accounts)
+    }
+}
+
+#[derive(Accounts)]
+#[instruction(project_bonding_id: u64, bond_id: u64)]
+pub struct MergeVesting<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+    /// CHECK: wallet that bonded the position, only used to derive the vesting info address
+    pub bonder: UncheckedAccount<'info>,
+    #[account(
+        seeds = [project_info.project_token.as_ref(), PROJECT_INFO_SEED.as_ref(), project_bonding_id.to_string().as_bytes()],
+        bump = project_info.bump,
+    )]
+    pub project_info: Box<Account<'info, ProjectInfo>>,
+    #[account(
+        mut,
+        seeds = [project_info.project_token.as_ref(), bonder.key().as_ref(), VESTING_INFO_SEED.as_ref(), bond_id.to_string().as_bytes()],
+        bump = vesting_info.bump,
+        constraint = vesting_info.current_owner(bonder.key) == user.key() @ BondError::NotVestingOwner,
+        constraint = vesting_info.project_info == project_info.key() @ BondError::WrongProject,
+        constraint = !vesting_info.is_tokenized() @ BondError::TokenizedPosition,
+        constraint = !vesting_info.revocable @ BondError::RevocablePosition,
+    )]
+    pub vesting_info: Box<Account<'info, VestingInfo>>,
+}
+
+#[derive(Accounts)]
+#[instruction(project_bonding_id: u64, bond_id: u64)]
+pub struct TransferVesting<'info> {
+    pub owner: Signer<'info>,
+    /// CHECK: wallet that bonded the position, only used to derive the vesting info address
+    pub bonder: UncheckedAccount<'info>,
+    #[account(
+        seeds = [project_info.project_token.as_ref(), PROJECT_INFO_SEED.as_ref(), project_bonding_id.to_string().as_bytes()],
+        bump = project_info.bump,
+    )]
+    pub project_info: Box<Account<'info, ProjectInfo>>,
+    #[account(
+        mut,
+        seeds = [project_info.project_token.as_ref(), bonder.key().as_ref(), VESTING_INFO_SEED.as_ref(), bond_id.to_string().as_bytes()],
+        bump = vesting_info.bump,
+        constraint = vesting_info.current_owner(bonder.key) == owner.key() @ BondError::NotVestingOwner,
+        constraint = !vesting_info.is_tokenized() @ BondError::TokenizedPosition,
+    )]
+    pub vesting_info: Box<Account<'info, VestingInfo>>,
+}
+
+#[derive(Accounts)]
+#[instruction(project_bonding_id: u64, bond_id: u64)]
+pub struct WithdrawVestingWithPosition<'info> {
+    #[account(mut)]
+    pub holder: Signer<'info>,
+    #[account(
+        constraint = position_token_account.mint == vesting_info.position_mint @ BondError::NotPositionHolder,
+        constraint = position_token_account.owner == holder.key() @ BondError::NotPositionHolder,
+        constraint = position_token_account.amount == 1 @ BondError::NotPositionHolder,
+    )]
+    pub position_token_account: Box<Account<'info, TokenAccount>>,
+    /// CHECK: wallet that bonded the position, only used to derive the vesting info address
+    pub bonder: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        constraint = holder_receive_token_account.mint == project_info.project_token @ BondError::InvalidReceiveTokenAccount,
+    )]
+    pub holder_receive_token_account: Account<'info, TokenAccount>,
+    #[account(
+        mut,
+        seeds = [project_info.project_token.as_ref(), TOKEN_VAULT_SEED.as_ref(), project_bonding_id.to_string().as_bytes()],
+        bump = project_info.vault_bump,
+    )]
+    pub vault_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        seeds = [project_info.project_token.as_ref(), PROJECT_INFO_SEED.as_ref(), project_bonding_id.to_string().as_bytes()],
+        bump = project_info.bump,
+    )]
+    pub project_info: Box<Account<'info, ProjectInfo>>,
+    #[account(
+        mut,
+        seeds = [project_info.project_token.as_ref(), bonder.key().as_ref(), VESTING_INFO_SEED.as_ref(), bond_id.to_string().as_bytes()],
+        bump = vesting_info.bump,
+        constraint = vesting_info.is_tokenized() @ BondError::NotPositionHolder,
+        constraint = vesting_info.project_info == project_info.key() @ BondError::WrongProject,
+    )]
+    pub vesting_info: Box<Account<'info, VestingInfo>>,
+    pub token_program: Program<'info, Token>,
+    pub clock: Sysvar<'info, Clock>,
+}
+
+impl<'info> WithdrawVestingWithPosition<'info> {
+    pub fn into_transfer_to_holder(&self) -> CpiContext<'_, '_, '_, 'info, Transfer<'info>> {
+        let cpi_accounts = Transfer {
+            from: self
+                .vault_account
+                .to_account_info()
+                .clone(),
+            to: self.holder_receive_token_account.to_account_info().clone(),
+            authority: self.vault_account.to_account_info().clone(),
+        };
+        CpiContext::new(self.token_program.to_account_info().clone(), cpi_accounts)
+    }
+}
+
+#[derive(Accounts)]
+#[instruction(project_bonding_id: u64, bond_id: u64)]
+pub struct SetClaimDelegate<'info> {
+    pub owner: Signer<'info>,
+    /// CHECK: wallet that bonded the position, only used to derive the vesting info address
+    pub bonder: UncheckedAccount<'info>,
+    #[account(
+        seeds = [project_info.project_token.as_ref(), PROJECT_INFO_SEED.as_ref(), project_bonding_id.to_string().as_bytes()],
+        bump = project_info.bump,
+    )]
+    pub project_info: Box<Account<'info, ProjectInfo>>,
+    #[account(
+        mut,
+        seeds = [project_info.project_token.as_ref(), bonder.key().as_ref(), VESTING_INFO_SEED.as_ref(), bond_id.to_string().as_bytes()],
+        bump = vesting_info.bump,
+        constraint = vesting_info.current_owner(bonder.key) == owner.key() @ BondError::NotVestingOwner,
+        constraint = !vesting_info.is_tokenized() @ BondError::TokenizedPosition,
+    )]
+    pub vesting_info: Box<Account<'info, VestingInfo>>,
+}
+
+#[derive(Accounts)]
+#[instruction(project_bonding_id: u64, grantee: Pubkey)]
+pub struct CreateGrant<'info> {
+    #[account(
+        mut,
+        constraint = *user.key == project_info.project_owner,
+    )]
+    pub user: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [project_info.project_token.as_ref(), PROJECT_INFO_SEED.as_ref(), project_bonding_id.to_string().as_bytes()],
+        bump = project_info.bump,
+    )]
+    pub project_info: Box<Account<'info, ProjectInfo>>,
+    #[account(
+        init_if_needed,
+        seeds = [project_info.project_token.as_ref(), grantee.as_ref(), BONDS_INFO_SEED.as_ref()],
+        bump,
+        payer = user,
+        space = 256 // 16 is enough for now
+    )]
+    pub bonds_info: Box<Account<'info, BondsInfo>>,
+    #[account(
+        init,
+        seeds = [project_info.project_token.as_ref(), grantee.as_ref(), VESTING_INFO_SEED.as_ref(), bonds_info.total_bonds.to_string().as_bytes()],
+        bump,
+        payer = user,
+        space = 256 // 32 is enough for now
+    )]
+    pub vesting_info: Box<Account<'info, VestingInfo>>,
+
+    pub system_program: Program<'info, System>,
+    pub clock: Sysvar<'info, Clock>
+}
+
+#[derive(Accounts)]
+#[instruction(project_bonding_id: u64, bond_id: u64)]
+pub struct Revoke<'info> {
+    #[account(
+        mut,
+        constraint = *user.key == project_info.project_owner,
+    )]
+    pub user: Signer<'info>,
+    /// CHECK: wallet the grant was created for, only used to derive the vesting info address
+    pub bonder: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        constraint = grantee_receive_token_account.mint == project_info.project_token @ BondError::InvalidReceiveTokenAccount,
+        constraint = grantee_receive_token_account.owner == vesting_info.current_owner(bonder.key) @ BondError::InvalidReceiveTokenAccount,
+    )]
+    pub grantee_receive_token_account: Account<'info, TokenAccount>,
+    #[account(
+        mut,
+        constraint = owner_receive_token_account.mint == project_info.project_token @ BondError::InvalidReceiveTokenAccount,
+    )]
+    pub owner_receive_token_account: Account<'info, TokenAccount>,
+    #[account(
+        mut,
+        seeds = [project_info.project_token.as_ref(), TOKEN_VAULT_SEED.as_ref(), project_bonding_id.to_string().as_bytes()],
+        bump = project_info.vault_bump,
+    )]
+    pub vault_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        seeds = [project_info.project_token.as_ref(), PROJECT_INFO_SEED.as_ref(), project_bonding_id.to_string().as_bytes()],
+        bump = project_info.bump,
+    )]
+    pub project_info: Box<Account<'info, ProjectInfo>>,
+    #[account(
+        mut,
+        seeds = [project_info.project_token.as_ref(), bonder.key().as_ref(), VESTING_INFO_SEED.as_ref(), bond_id.to_string().as_bytes()],
+        bump = vesting_info.bump,
+        constraint = vesting_info.revocable @ BondError::NotRevocable,
+        constraint = vesting_info.project_info == project_info.key() @ BondError::WrongProject,
+    )]
+    pub vesting_info: Box<Account<'info, VestingInfo>>,
+    pub token_program: Program<'info, Token>,
+    pub clock: Sysvar<'info, Clock>,
+}
+
+impl<'info> Revoke<'info> {
+    pub fn into_transfer_to_grantee(&self) -> CpiContext<'_, '_, '_, 'info, Transfer<'info>> {
+        let cpi_accounts = Transfer {
+            from: self
+                .vault_account
+                .to_account_info()
+                .clone(),
+            to: self.grantee_receive_token_account.to_account_info().clone(),
+            authority: self.vault_account.to_account_info().clone(),
+        };
+        CpiContext::new(self.token_program.to_account_info().clone(), cpi_accounts)
+    }
+
+    pub fn into_transfer_to_owner(&self) -> CpiContext<'_, '_, '_, 'info, Transfer<'info>> {
+        let cpi_accounts = Transfer {
+            from: self
+                .vault_account
+                .to_account_info()
+                .clone(),
+            to: self.owner_receive_token_account.to_account_info().clone(),
+            authority: self.vault_account.to_account_info().clone(),
+        };
+        CpiContext::new(self.token_program.to_account_info().clone(), cpi_accounts)
+    }
+}
+
+#[derive(Accounts)]
+#[instruction(project_bonding_id: u64, lp_amount: u64, beneficiary: Pubkey)]
+pub struct BondFor<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+    pub lp_mint: Account<'info, Mint>,
+    #[account(mut)]
+    pub lp_deposit_account: Account<'info, TokenAccount>,
+    #[account(
+        mut,
+        constraint = project_info.lp_token_account == lp_recieve_account.key()
+    )]
+    pub lp_recieve_account: Account<'info, TokenAccount>,
+    pub token_mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [token_mint.key().as_ref(), PROJECT_INFO_SEED.as_ref(), project_bonding_id.to_string().as_bytes()],
+        bump = project_info.bump,
+    )]
+    pub project_info: Box<Account<'info, ProjectInfo>>,
+    #[account(
+        init_if_needed,
+        seeds = [token_mint.key().as_ref(), beneficiary.as_ref(), BONDS_INFO_SEED.as_ref()],
+        bump,
+        payer = user,
+        space = 256 // 16 is enough for now
+    )]
+    pub bonds_info: Box<Account<'info, BondsInfo>>,
+    #[account(
+        init,
+        seeds = [token_mint.key().as_ref(), beneficiary.as_ref(), VESTING_INFO_SEED.as_ref(), bonds_info.total_bonds.to_string().as_bytes()],
+        bump,
+        payer = user,
+        space = 256 // 32 is enough for now
+    )]
+    pub vesting_info: Box<Account<'info, VestingInfo>>,
+
+    pub system_program: Program<'info, System>,
+    pub token_program: Program<'info, Token>,
+    pub clock: Sysvar<'info, Clock>
+}
+
+impl<'info> BondFor<'info> {
+    pub fn into_bond_lp_to_project_context(&self) -> CpiContext<'_, '_, '_, 'info, Transfer<'info>> {
+        let cpi_accounts = Transfer {
+            from: self
+                .lp_deposit_account
+                .to_account_info()
+                .clone(),
+            to: self.lp_recieve_account.to_account_info().clone(),
+            authority: self.user.to_account_info().clone(),
+        };
+        CpiContext::new(self.token_program.to_account_info().clone(), cpi_accounts)
     }
 }
+
+#[derive(Accounts)]
+#[instruction(project_bonding_id: u64, bond_id: u64)]
+pub struct ViewVesting<'info> {
+    /// CHECK: wallet that bonded the position, only used to derive the vesting info address
+    pub bonder: UncheckedAccount<'info>,
+    #[account(
+        seeds = [project_info.project_token.as_ref(), PROJECT_INFO_SEED.as_ref(), project_bonding_id.to_string().as_bytes()],
+        bump = project_info.bump,
+    )]
+    pub project_info: Box<Account<'info, ProjectInfo>>,
+    #[account(
+        seeds = [project_info.project_token.as_ref(), bonder.key().as_ref(), VESTING_INFO_SEED.as_ref(), bond_id.to_string().as_bytes()],
+        bump = vesting_info.bump,
+        constraint = vesting_info.project_info == project_info.key() @ BondError::WrongProject,
+    )]
+    pub vesting_info: Box<Account<'info, VestingInfo>>,
+    pub clock: Sysvar<'info, Clock>,
+}
+
+#[derive(Accounts)]
+#[instruction(project_bonding_id: u64, bond_id: u64)]
+pub struct MigrateVesting<'info> {
+    /// Project owner, whose vault pays the position out once migrated
+    #[account(
+        constraint = *user.key == project_info.project_owner,
+    )]
+    pub user: Signer<'info>,
+    /// Owner of the position, agreeing on the project it is bound to
+    pub owner: Signer<'info>,
+    /// CHECK: wallet that bonded the position, only used to derive the vesting info address
+    pub bonder: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        seeds = [project_info.project_token.as_ref(), PROJECT_INFO_SEED.as_ref(), project_bonding_id.to_string().as_bytes()],
+        bump = project_info.bump,
+    )]
+    pub project_info: Box<Account<'info, ProjectInfo>>,
+    #[account(
+        mut,
+        seeds = [project_info.project_token.as_ref(), bonder.key().as_ref(), VESTING_INFO_SEED.as_ref(), bond_id.to_string().as_bytes()],
+        bump,
+        constraint = vesting_info.current_owner(bonder.key) == owner.key() @ BondError::NotVestingOwner,
+        constraint = vesting_info.project_info == Pubkey::default() @ BondError::AlreadyMigrated,
+    )]
+    pub vesting_info: Box<Account<'info, VestingInfo>>,
+}
+
+#[derive(Accounts)]
+#[instruction(project_bonding_id: u64)]
+pub struct StoreProjectBumps<'info> {
+    #[account(
+        mut,
+        seeds = [project_info.project_token.as_ref(), PROJECT_INFO_SEED.as_ref(), project_bonding_id.to_string().as_bytes()],
+        bump,
+    )]
+    pub project_info: Box<Account<'info, ProjectInfo>>,
+    #[account(
+        seeds = [project_info.project_token.as_ref(), TOKEN_VAULT_SEED.as_ref(), project_bonding_id.to_string().as_bytes()],
+        bump,
+    )]
+    pub vault_account: Box<Account<'info, TokenAccount>>,
+}
+
+#[derive(Accounts)]
+#[instruction(project_bonding_id: u64)]
+pub struct QuoteBond<'info> {
+    pub lp_mint: Account<'info, Mint>,
+    pub token_mint: Account<'info, Mint>,
+    #[account(
+        seeds = [token_mint.key().as_ref(), PROJECT_INFO_SEED.as_ref(), project_bonding_id.to_string().as_bytes()],
+        bump = project_info.bump,
+        constraint = project_info.lp_token == lp_mint.key(),
+    )]
+    pub project_info: Box<Account<'info, ProjectInfo>>,
+}
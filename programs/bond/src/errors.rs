@@ -0,0 +1,52 @@
+use anchor_lang::prelude::*;
+
+#[error_code]
+pub enum BondError {
+    #[msg("The milestone table must be sorted by time, never decrease and end at 100%")]
+    InvalidMilestoneTable,
+
+    #[msg("The milestone table of the project was not provided")]
+    MissingMilestoneTable,
+
+    #[msg("The vesting schedule can not be changed once the project has bonds")]
+    ProjectAlreadyBonded,
+
+    #[msg("The number of vesting accounts does not match the number of bond ids")]
+    VestingAccountsMismatch,
+
+    #[msg("The provided vesting account does not belong to the given bond")]
+    InvalidVestingInfo,
+
+    #[msg("A vesting position can not be merged into itself")]
+    SelfMerge,
+
+    #[msg("The signer does not own the vesting position")]
+    NotVestingOwner,
+
+    #[msg("The vesting position is tokenized and can only be used by the holder of its token")]
+    TokenizedPosition,
+
+    #[msg("The signer does not hold the token of the vesting position")]
+    NotPositionHolder,
+
+    #[msg("The receiving token account does not hold the project token")]
+    InvalidReceiveTokenAccount,
+
+    #[msg("The vesting position is not revocable")]
+    NotRevocable,
+
+    #[msg("The vesting position belongs to another project")]
+    WrongProject,
+
+    #[msg("The vesting position is already bound to a project")]
+    AlreadyMigrated,
+
+    #[msg("A revocable vesting position can not be merged")]
+    RevocablePosition,
+
+    #[msg("The discounted price is zero or out of range")]
+    InvalidPrice,
+
+    #[msg("The withdrawn amount is below the recorded one or above the position")]
+    InvalidWithdrawnAmount,
+}
@@ -4,6 +4,8 @@ use anchor_lang::prelude::*;
 pub mod constant;
 /// context accounts
 pub mod context_accounts;
+/// errors
+pub mod errors;
 /// processor
 pub mod processor;
 /// states
@@ -29,11 +31,81 @@ pub mod bond {
         process_update_price(ctx, new_price)
     }
 
+    pub fn init_milestone_table(ctx: Context<InitMilestoneTable>, _project_bonding_id: u64, time_mode: u64, milestones: Vec<Milestone>) -> Result<()> {
+        process_init_milestone_table(ctx, time_mode, milestones)
+    }
+
+    /// Bonds `lp_amount` lp tokens of the signer. CPI-safe: `user` may be a PDA of the calling program.
     pub fn bond(ctx: Context<Bond>, _project_bonding_id: u64, lp_amount: u64) -> Result<()> {
         process_bond(ctx, lp_amount)
     }
 
+    /// Withdraws the vested tokens of a position. CPI-safe: `taker` may be a PDA owning or delegated the position.
     pub fn withdraw_vesting(ctx: Context<WithdrawVesting>, project_bonding_id: u64, _bond_id: u64) -> Result<()> {
         process_withdraw_vesting(ctx, project_bonding_id)
     }
+
+    pub fn withdraw_all_vesting<'info>(ctx: Context<'_, '_, '_, 'info, WithdrawAllVesting<'info>>, project_bonding_id: u64, bond_ids: Vec<u64>) -> Result<()> {
+        process_withdraw_all_vesting(ctx, project_bonding_id, bond_ids)
+    }
+
+    pub fn merge_vesting<'info>(ctx: Context<'_, '_, '_, 'info, MergeVesting<'info>>, _project_bonding_id: u64, _bond_id: u64, merged_bond_ids: Vec<u64>) -> Result<()> {
+        process_merge_vesting(ctx, merged_bond_ids)
+    }
+
+    pub fn transfer_vesting(ctx: Context<TransferVesting>, _project_bonding_id: u64, _bond_id: u64, new_owner: Pubkey) -> Result<()> {
+        process_transfer_vesting(ctx, new_owner)
+    }
+
+    pub fn bond_with_position_mint(ctx: Context<BondWithPositionMint>, _project_bonding_id: u64, lp_amount: u64) -> Result<()> {
+        process_bond_with_position_mint(ctx, lp_amount)
+    }
+
+    pub fn withdraw_vesting_with_position(ctx: Context<WithdrawVestingWithPosition>, project_bonding_id: u64, _bond_id: u64) -> Result<()> {
+        process_withdraw_vesting_with_position(ctx, project_bonding_id)
+    }
+
+    pub fn set_claim_delegate(ctx: Context<SetClaimDelegate>, _project_bonding_id: u64, _bond_id: u64, delegate: Option<Pubkey>) -> Result<()> {
+        process_set_claim_delegate(ctx, delegate)
+    }
+
+    pub fn create_grant(ctx: Context<CreateGrant>, _project_bonding_id: u64, grantee: Pubkey, amount: u64) -> Result<()> {
+        process_create_grant(ctx, grantee, amount)
+    }
+
+    pub fn revoke(ctx: Context<Revoke>, project_bonding_id: u64, _bond_id: u64) -> Result<()> {
+        process_revoke(ctx, project_bonding_id)
+    }
+
+    /// Bonds `lp_amount` lp tokens of the signer into a position owned by `beneficiary`.
+    /// CPI-safe: `user` may be a PDA of the calling program paying on behalf of its users.
+    pub fn bond_for(ctx: Context<BondFor>, _project_bonding_id: u64, lp_amount: u64, beneficiary: Pubkey) -> Result<()> {
+        process_bond_for(ctx, lp_amount, beneficiary)
+    }
+
+    /// Sets a `VestingStatus` of the position as return data without changing any state.
+    /// Callers read it with `VestingStatus::from_return_data` after the CPI, or through transaction simulation.
+    pub fn view_vesting(ctx: Context<ViewVesting>, _project_bonding_id: u64, _bond_id: u64) -> Result<()> {
+        process_view_vesting(ctx)
+    }
+
+    /// Sets the amount of project tokens `bond` would vest for `lp_amount` as return data.
+    pub fn quote_bond(ctx: Context<QuoteBond>, _project_bonding_id: u64, lp_amount: u64) -> Result<()> {
+        process_quote_bond(ctx, lp_amount)
+    }
+
+    /// Sets the amount `withdraw_vesting` would currently transfer as return data.
+    pub fn quote_claimable(ctx: Context<ViewVesting>, _project_bonding_id: u64, _bond_id: u64) -> Result<()> {
+        process_quote_claimable(ctx)
+    }
+
+    /// Binds a position bonded before positions stored their project to the project.
+    pub fn migrate_vesting(ctx: Context<MigrateVesting>, _project_bonding_id: u64, _bond_id: u64, withdrawn_amount: u64) -> Result<()> {
+        process_migrate_vesting(ctx, withdrawn_amount)
+    }
+
+    /// Stores the canonical bumps of a project created before bumps were stored. Callable by anyone.
+    pub fn store_project_bumps(ctx: Context<StoreProjectBumps>, _project_bonding_id: u64) -> Result<()> {
+        process_store_project_bumps(ctx)
+    }
 }
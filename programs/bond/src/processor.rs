@@ -3,9 +3,37 @@ pub mod process_update_authority;
 pub mod process_bond;
 pub mod process_withdraw_vesting;
 pub mod process_update_price;
+pub mod process_init_milestone_table;
+pub mod process_withdraw_all_vesting;
+pub mod process_merge_vesting;
+pub mod process_transfer_vesting;
+pub mod process_bond_with_position_mint;
+pub mod process_withdraw_vesting_with_position;
+pub mod process_set_claim_delegate;
+pub mod process_create_grant;
+pub mod process_revoke;
+pub mod process_bond_for;
+pub mod process_view_vesting;
+pub mod process_quote;
+pub mod process_migrate_vesting;
+pub mod process_store_project_bumps;
 
 pub use process_init_new_project::*;
 pub use process_update_authority::*;
 pub use process_bond::*;
 pub use process_withdraw_vesting::*;
-pub use process_update_price::*;
\ No newline at end of file
+pub use process_update_price::*;
+pub use process_init_milestone_table::*;
+pub use process_withdraw_all_vesting::*;
+pub use process_merge_vesting::*;
+pub use process_transfer_vesting::*;
+pub use process_bond_with_position_mint::*;
+pub use process_withdraw_vesting_with_position::*;
+pub use process_set_claim_delegate::*;
+pub use process_create_grant::*;
+pub use process_revoke::*;
+pub use process_bond_for::*;
+pub use process_view_vesting::*;
+pub use process_quote::*;
+pub use process_migrate_vesting::*;
+pub use process_store_project_bumps::*;
\ No newline at end of file
@@ -0,0 +1,34 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token;
+
+use crate::context_accounts::*;
+use crate::states::VestingInfo;
+
+pub fn process_bond_for(
+    ctx: Context<BondFor>,
+    lp_amount: u64,
+    beneficiary: Pubkey,
+) -> Result<()> {
+    ctx.accounts.bonds_info.total_bonds += 1;
+
+    let project_info_key = ctx.accounts.project_info.key();
+    let vesting_info = ctx.accounts.project_info.bond_position(
+        project_info_key,
+        beneficiary,
+        lp_amount,
+        ctx.accounts.token_mint.decimals,
+        ctx.accounts.lp_mint.decimals,
+        ctx.accounts.clock.unix_timestamp as u64,
+    )?;
+    ctx.accounts.vesting_info.set_inner(VestingInfo {
+        bump: *ctx.bumps.get("vesting_info").unwrap(),
+        ..vesting_info
+    });
+
+    token::transfer(
+        ctx.accounts.into_bond_lp_to_project_context(),
+        lp_amount
+    )?;
+
+    Ok(())
+}
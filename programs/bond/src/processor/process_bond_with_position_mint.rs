@@ -0,0 +1,63 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, spl_token::instruction::AuthorityType};
+
+use crate::constant::POSITION_MINT_SEED;
+use crate::context_accounts::*;
+use crate::states::VestingInfo;
+
+/// Bonds like `bond` and mints a single token representing the vesting position to the user.
+/// The position mint authority is removed afterwards so its supply stays 1.
+/// A `PositionInfo` derived from the mint points back to the position.
+pub fn process_bond_with_position_mint(
+    ctx: Context<BondWithPositionMint>,
+    lp_amount: u64,
+) -> Result<()> {
+    let bond_id = ctx.accounts.bonds_info.total_bonds;
+    ctx.accounts.bonds_info.total_bonds += 1;
+
+    let project_info_key = ctx.accounts.project_info.key();
+    let vesting_info = ctx.accounts.project_info.bond_position(
+        project_info_key,
+        ctx.accounts.user.key(),
+        lp_amount,
+        ctx.accounts.token_mint.decimals,
+        ctx.accounts.lp_mint.decimals,
+        ctx.accounts.clock.unix_timestamp as u64,
+    )?;
+    ctx.accounts.vesting_info.set_inner(VestingInfo {
+        position_mint: ctx.accounts.position_mint.key(),
+        bump: *ctx.bumps.get("vesting_info").unwrap(),
+        ..vesting_info
+    });
+
+    token::transfer(
+        ctx.accounts.into_bond_lp_to_project_context(),
+        lp_amount
+    )?;
+
+    ctx.accounts.position_info.vesting_info = ctx.accounts.vesting_info.key();
+    ctx.accounts.position_info.project_info = ctx.accounts.project_info.key();
+    ctx.accounts.position_info.bonder = ctx.accounts.user.key();
+    ctx.accounts.position_info.bond_id = bond_id;
+    ctx.accounts.position_info.bump = *ctx.bumps.get("position_info").unwrap();
+
+    let vesting_info_key = ctx.accounts.vesting_info.key();
+    let position_mint_bump = *ctx.bumps.get("position_mint").unwrap();
+    let position_mint_seeds: &[&[u8]] = &[
+        vesting_info_key.as_ref(),
+        POSITION_MINT_SEED.as_ref(),
+        &[position_mint_bump],
+    ];
+
+    token::mint_to(
+        ctx.accounts.into_mint_position_context().with_signer(&[position_mint_seeds]),
+        1,
+    )?;
+    token::set_authority(
+        ctx.accounts.into_lock_position_supply_context().with_signer(&[position_mint_seeds]),
+        AuthorityType::MintTokens,
+        None,
+    )?;
+
+    Ok(())
+}
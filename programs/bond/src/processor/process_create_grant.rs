@@ -0,0 +1,25 @@
+use anchor_lang::prelude::*;
+
+use crate::context_accounts::*;
+
+/// Allocates `amount` project tokens from the vault to a revocable vesting position of `grantee`.
+/// The grant is indexed with the grantee's bonds, so it is withdrawn like any other position.
+pub fn process_create_grant(
+    ctx: Context<CreateGrant>,
+    grantee: Pubkey,
+    amount: u64
+) -> Result<()> {
+    ctx.accounts.bonds_info.total_bonds += 1;
+
+    ctx.accounts.project_info.record_bond(0, amount)?;
+
+    ctx.accounts.vesting_info.total_amount = amount;
+    ctx.accounts.vesting_info.start_time = ctx.accounts.clock.unix_timestamp as u64;
+    ctx.accounts.vesting_info.bonder = grantee;
+    ctx.accounts.vesting_info.project_info = ctx.accounts.project_info.key();
+    ctx.accounts.vesting_info.bump = *ctx.bumps.get("vesting_info").unwrap();
+    ctx.accounts.vesting_info.owner = grantee;
+    ctx.accounts.vesting_info.revocable = true;
+
+    Ok(())
+}
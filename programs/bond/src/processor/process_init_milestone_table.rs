@@ -0,0 +1,23 @@
+use anchor_lang::prelude::*;
+
+use crate::context_accounts::*;
+use crate::errors::BondError;
+use crate::states::*;
+
+pub fn process_init_milestone_table(
+    ctx: Context<InitMilestoneTable>,
+    time_mode: u64,
+    milestones: Vec<Milestone>
+) -> Result<()> {
+    ctx.accounts.milestone_table.project_info = ctx.accounts.project_info.key();
+    ctx.accounts.milestone_table.time_mode = time_mode;
+    ctx.accounts.milestone_table.milestones = milestones;
+
+    if !ctx.accounts.milestone_table.is_valid() {
+        msg!("Milestones must be sorted by time, never decrease and end at 100%");
+        return Err(BondError::InvalidMilestoneTable.into());
+    }
+
+    ctx.accounts.project_info.milestone_table = ctx.accounts.milestone_table.key();
+    Ok(())
+}
@@ -0,0 +1,51 @@
+use anchor_lang::prelude::*;
+use anchor_lang::AccountsClose;
+use std::convert::TryFrom;
+
+use crate::context_accounts::*;
+use crate::errors::BondError;
+use crate::states::*;
+
+/// Merges the listed bonds owned by the user into the vesting info and closes them, returning their rent to the user.
+/// Remaining accounts: one `VestingInfo` per merged bond id, each derived from the bonder stored in it.
+/// The merged position starts at the start times of all positions weighted by their total amounts.
+pub fn process_merge_vesting<'info>(
+    ctx: Context<'_, '_, '_, 'info, MergeVesting<'info>>,
+    merged_bond_ids: Vec<u64>
+) -> Result<()> {
+    if ctx.remaining_accounts.len() != merged_bond_ids.len() {
+        return Err(BondError::VestingAccountsMismatch.into());
+    }
+
+    let mut total_amount = ctx.accounts.vesting_info.total_amount as u128;
+    let mut withdrawn_amount = ctx.accounts.vesting_info.withdrawn_amount as u128;
+    let mut weighted_start_time =
+        ctx.accounts.vesting_info.start_time as u128 * ctx.accounts.vesting_info.total_amount as u128;
+
+    for (merged_bond_id, account_info) in merged_bond_ids.iter().zip(ctx.remaining_accounts.iter()) {
+        if account_info.key() == ctx.accounts.vesting_info.key() {
+            return Err(BondError::SelfMerge.into());
+        }
+        let merged_vesting_info: Account<'info, VestingInfo> = Account::try_from(account_info)?;
+        let bonder = merged_vesting_info.stored_bonder_or(ctx.accounts.user.key);
+        let vesting_info_key = merged_vesting_info.address(&ctx.accounts.project_info.project_token, &bonder, *merged_bond_id);
+        if vesting_info_key != Some(account_info.key()) {
+            return Err(BondError::InvalidVestingInfo.into());
+        }
+        merged_vesting_info.check_mergeable(&bonder, ctx.accounts.user.key, &ctx.accounts.project_info.key())?;
+        total_amount += merged_vesting_info.total_amount as u128;
+        withdrawn_amount += merged_vesting_info.withdrawn_amount as u128;
+        weighted_start_time +=
+            merged_vesting_info.start_time as u128 * merged_vesting_info.total_amount as u128;
+
+        merged_vesting_info.close(ctx.accounts.user.to_account_info())?;
+    }
+
+    if let Some(start_time) = weighted_start_time.checked_div(total_amount) {
+        ctx.accounts.vesting_info.start_time = u64::try_from(start_time).unwrap();
+    }
+    ctx.accounts.vesting_info.total_amount = u64::try_from(total_amount).unwrap();
+    ctx.accounts.vesting_info.withdrawn_amount = u64::try_from(withdrawn_amount).unwrap();
+
+    Ok(())
+}
@@ -0,0 +1,18 @@
+use anchor_lang::prelude::*;
+
+use crate::context_accounts::*;
+
+/// Binds a position created before positions stored their project to the project,
+/// agreed on by the project owner and the owner of the position.
+/// Withdrawals from such positions were paid out without being stored on the position, and no other account
+/// recorded them, so both signers state `withdrawn_amount` from the vault's transfer history.
+pub fn process_migrate_vesting(
+    ctx: Context<MigrateVesting>,
+    withdrawn_amount: u64,
+) -> Result<()> {
+    let paid_out_amount = ctx.accounts.vesting_info.migrate_withdrawn_amount(withdrawn_amount)?;
+    ctx.accounts.project_info.record_withdrawal(paid_out_amount);
+    ctx.accounts.vesting_info.project_info = ctx.accounts.project_info.key();
+    ctx.accounts.vesting_info.bump = *ctx.bumps.get("vesting_info").unwrap();
+    Ok(())
+}
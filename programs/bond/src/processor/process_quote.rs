@@ -0,0 +1,33 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::program::set_return_data;
+
+use crate::constant::ACCURACY;
+use crate::context_accounts::*;
+
+pub fn process_quote_bond(
+    ctx: Context<QuoteBond>,
+    lp_amount: u64,
+) -> Result<()> {
+    let vesting_amount = ctx.accounts.project_info.bond_vesting_amount(
+        lp_amount,
+        ctx.accounts.token_mint.decimals,
+        ctx.accounts.lp_mint.decimals,
+    )?;
+    set_return_data(&vesting_amount.to_le_bytes());
+    Ok(())
+}
+
+pub fn process_quote_claimable(
+    ctx: Context<ViewVesting>
+) -> Result<()> {
+    let milestone_table = ctx.accounts.project_info.load_milestone_table(ctx.remaining_accounts)?;
+    let vested_amount = ctx.accounts.vesting_info.vested_amount(
+        &ctx.accounts.project_info,
+        ctx.accounts.clock.unix_timestamp as u64,
+        ACCURACY,
+        milestone_table.as_deref(),
+    );
+    let claimable_amount = ctx.accounts.vesting_info.claimable_amount(vested_amount);
+    set_return_data(&claimable_amount.to_le_bytes());
+    Ok(())
+}
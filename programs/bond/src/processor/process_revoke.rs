@@ -0,0 +1,52 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token;
+
+use crate::constant::{ACCURACY, TOKEN_VAULT_SEED};
+use crate::context_accounts::*;
+
+/// Pays out the vested part of a grant to the grantee and returns the unvested remainder to the project owner.
+pub fn process_revoke(
+    ctx: Context<Revoke>,
+    project_bonding_id: u64
+) -> Result<()> {
+    let milestone_table = ctx.accounts.project_info.load_milestone_table(ctx.remaining_accounts)?;
+    let vested_amount = ctx.accounts.vesting_info.vested_amount(
+        &ctx.accounts.project_info,
+        ctx.accounts.clock.unix_timestamp as u64,
+        ACCURACY,
+        milestone_table.as_deref(),
+    );
+    let withdrawable_amount = ctx.accounts.vesting_info.record_withdrawal(vested_amount);
+    let unvested_amount = ctx.accounts.vesting_info.total_amount - vested_amount;
+
+    ctx.accounts.vesting_info.total_amount = vested_amount;
+    ctx.accounts.vesting_info.revocable = false;
+
+    ctx.accounts.project_info.token_amount -= unvested_amount;
+    ctx.accounts.project_info.vested_amount -= unvested_amount;
+    ctx.accounts.project_info.record_withdrawal(withdrawable_amount);
+
+    let project_bonding_id = project_bonding_id.to_string();
+    let vault_account_bump = ctx.accounts.project_info.vault_bump;
+    let vault_account_seeds: &[&[u8]] = &[
+        ctx.accounts.project_info.project_token.as_ref(),
+        TOKEN_VAULT_SEED.as_ref(),
+        project_bonding_id.as_bytes(),
+        &[vault_account_bump],
+    ];
+
+    if withdrawable_amount > 0 {
+        token::transfer(
+            ctx.accounts.into_transfer_to_grantee().with_signer(&[vault_account_seeds]),
+            withdrawable_amount,
+        )?;
+    }
+    if unvested_amount > 0 {
+        token::transfer(
+            ctx.accounts.into_transfer_to_owner().with_signer(&[vault_account_seeds]),
+            unvested_amount,
+        )?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,12 @@
+use anchor_lang::prelude::*;
+
+use crate::context_accounts::*;
+
+/// Stores the canonical bumps of a project created before bumps were stored.
+pub fn process_store_project_bumps(
+    ctx: Context<StoreProjectBumps>
+) -> Result<()> {
+    ctx.accounts.project_info.bump = *ctx.bumps.get("project_info").unwrap();
+    ctx.accounts.project_info.vault_bump = *ctx.bumps.get("vault_account").unwrap();
+    Ok(())
+}
@@ -0,0 +1,12 @@
+use anchor_lang::prelude::*;
+
+use crate::context_accounts::*;
+
+pub fn process_transfer_vesting(
+    ctx: Context<TransferVesting>,
+    new_owner: Pubkey
+) -> Result<()> {
+    let bonder = ctx.accounts.bonder.key();
+    ctx.accounts.vesting_info.transfer(&bonder, &new_owner);
+    Ok(())
+}
@@ -0,0 +1,26 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::program::set_return_data;
+
+use crate::constant::ACCURACY;
+use crate::context_accounts::*;
+use crate::states::*;
+
+pub fn process_view_vesting(
+    ctx: Context<ViewVesting>
+) -> Result<()> {
+    let milestone_table = ctx.accounts.project_info.load_milestone_table(ctx.remaining_accounts)?;
+    let vested_amount = ctx.accounts.vesting_info.vested_amount(
+        &ctx.accounts.project_info,
+        ctx.accounts.clock.unix_timestamp as u64,
+        ACCURACY,
+        milestone_table.as_deref(),
+    );
+
+    let status = VestingStatus {
+        total_amount: ctx.accounts.vesting_info.total_amount,
+        vested_amount,
+        withdrawn_amount: ctx.accounts.vesting_info.withdrawn_amount,
+    };
+    set_return_data(&status.try_to_vec().map_err(|_| ErrorCode::AccountDidNotSerialize)?);
+    Ok(())
+}
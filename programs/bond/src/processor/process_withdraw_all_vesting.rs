@@ -0,0 +1,64 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token;
+
+use crate::constant::{ACCURACY, TOKEN_VAULT_SEED};
+use crate::context_accounts::*;
+use crate::errors::BondError;
+use crate::states::*;
+
+/// Withdraws every listed bond owned by the taker in a single transfer, whoever bonded it.
+/// Remaining accounts: the project's milestone table if it has one, then one `VestingInfo` per bond id,
+/// each derived from the bonder stored in it.
+pub fn process_withdraw_all_vesting<'info>(
+    ctx: Context<'_, '_, '_, 'info, WithdrawAllVesting<'info>>,
+    project_bonding_id: u64,
+    bond_ids: Vec<u64>
+) -> Result<()> {
+    let milestone_table = ctx.accounts.project_info.load_milestone_table(ctx.remaining_accounts)?;
+    let vesting_accounts = if milestone_table.is_some() {
+        &ctx.remaining_accounts[1..]
+    } else {
+        ctx.remaining_accounts
+    };
+    if vesting_accounts.len() != bond_ids.len() {
+        return Err(BondError::VestingAccountsMismatch.into());
+    }
+
+    let now = ctx.accounts.clock.unix_timestamp as u64;
+    let mut withdrawable_amount: u64 = 0;
+    for (bond_id, account_info) in bond_ids.iter().zip(vesting_accounts.iter()) {
+        let mut vesting_info: Account<'info, VestingInfo> = Account::try_from(account_info)?;
+        let bonder = vesting_info.stored_bonder_or(ctx.accounts.taker.key);
+        let vesting_info_key = vesting_info.address(&ctx.accounts.project_info.project_token, &bonder, *bond_id);
+        if vesting_info_key != Some(account_info.key()) {
+            return Err(BondError::InvalidVestingInfo.into());
+        }
+        vesting_info.check_batch_withdrawable(&bonder, ctx.accounts.taker.key, &ctx.accounts.project_info.key())?;
+        let vested_amount = vesting_info.vested_amount(
+            &ctx.accounts.project_info,
+            now,
+            ACCURACY,
+            milestone_table.as_deref(),
+        );
+        withdrawable_amount += vesting_info.record_withdrawal(vested_amount);
+        vesting_info.exit(ctx.program_id)?;
+    }
+
+    if withdrawable_amount > 0 {
+        ctx.accounts.project_info.record_withdrawal(withdrawable_amount);
+
+        let vault_account_bump = ctx.accounts.project_info.vault_bump;
+
+        token::transfer(
+            ctx.accounts.into_transfer_to_taker().with_signer(&[&[
+                ctx.accounts.project_info.project_token.as_ref(),
+                TOKEN_VAULT_SEED.as_ref(),
+                project_bonding_id.to_string().as_bytes(),
+                &[vault_account_bump],
+            ]]),
+            withdrawable_amount,
+        )?;
+    }
+
+    Ok(())
+}
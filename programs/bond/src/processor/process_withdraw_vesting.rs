@@ -8,16 +8,13 @@ pub fn process_withdraw_vesting(
     ctx: Context<WithdrawVesting>,
     project_bonding_id: u64
 ) -> Result<()> {
-    let vested_amount = ctx.accounts.taker_vested_amount(ACCURACY);
-    let withdrawable_amount = vested_amount - ctx.accounts.vesting_info.withdrawn_amount;
+    let milestone_table = ctx.accounts.project_info.load_milestone_table(ctx.remaining_accounts)?;
+    let vested_amount = ctx.accounts.taker_vested_amount(ACCURACY, milestone_table.as_deref());
+    let withdrawable_amount = ctx.accounts.vesting_info.record_withdrawal(vested_amount);
     if withdrawable_amount > 0 {
-        ctx.accounts.vesting_info.withdrawn_amount = vested_amount;
+        ctx.accounts.project_info.record_withdrawal(withdrawable_amount);
 
-        let (vault_account, vault_account_bump) = Pubkey::find_program_address(&[
-                ctx.accounts.project_info.project_token.as_ref(),
-                TOKEN_VAULT_SEED.as_ref(),
-                project_bonding_id.to_string().as_bytes(),
-            ], &ctx.program_id);
+        let vault_account_bump = ctx.accounts.project_info.vault_bump;
 
         token::transfer(
             ctx.accounts.into_transfer_to_taker().with_signer(&[&[
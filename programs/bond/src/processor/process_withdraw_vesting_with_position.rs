@@ -0,0 +1,37 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token;
+
+use crate::constant::{ACCURACY, TOKEN_VAULT_SEED};
+use crate::context_accounts::*;
+
+/// Withdraws a tokenized vesting position to the holder of its position token.
+pub fn process_withdraw_vesting_with_position(
+    ctx: Context<WithdrawVestingWithPosition>,
+    project_bonding_id: u64
+) -> Result<()> {
+    let milestone_table = ctx.accounts.project_info.load_milestone_table(ctx.remaining_accounts)?;
+    let vested_amount = ctx.accounts.vesting_info.vested_amount(
+        &ctx.accounts.project_info,
+        ctx.accounts.clock.unix_timestamp as u64,
+        ACCURACY,
+        milestone_table.as_deref(),
+    );
+    let withdrawable_amount = ctx.accounts.vesting_info.record_withdrawal(vested_amount);
+    if withdrawable_amount > 0 {
+        ctx.accounts.project_info.record_withdrawal(withdrawable_amount);
+
+        let vault_account_bump = ctx.accounts.project_info.vault_bump;
+
+        token::transfer(
+            ctx.accounts.into_transfer_to_holder().with_signer(&[&[
+                ctx.accounts.project_info.project_token.as_ref(),
+                TOKEN_VAULT_SEED.as_ref(),
+                project_bonding_id.to_string().as_bytes(),
+                &[vault_account_bump],
+            ]]),
+            withdrawable_amount,
+        )?;
+    }
+
+    Ok(())
+}
@@ -1,4 +1,9 @@
 use anchor_lang::prelude::*;
+use anchor_lang::solana_program::program::get_return_data;
+use bonding_math::{Decimal, Rounding};
+
+use crate::constant::{ACCURACY, BIPS, MAX_MILESTONES, VESTING_INFO_SEED};
+use crate::errors::BondError;
 
 #[derive(Default, Clone, AnchorSerialize, AnchorDeserialize)]
 pub struct VestingSchedule {
@@ -27,6 +32,28 @@ pub struct DiscountSettings {
     pub discount_mode: u64,
 }
 
+/// Vesting position status returned by `view_vesting` through return data
+#[derive(Default, Clone, AnchorSerialize, AnchorDeserialize)]
+pub struct VestingStatus {
+    /// Total amount of tokens to be vested.
+    pub total_amount: u64,
+    /// The amount vested so far, withdrawn or not.
+    pub vested_amount: u64,
+    /// The amount that has been withdrawn.
+    pub withdrawn_amount: u64,
+}
+
+impl VestingStatus {
+    /// Reads the status set by `view_vesting`, for programs calling it through CPI.
+    pub fn from_return_data() -> Option<Self> {
+        let (program_id, data) = get_return_data()?;
+        if program_id != crate::ID {
+            return None;
+        }
+        Self::try_from_slice(&data).ok()
+    }
+}
+
 #[account]
 #[derive(Default)]
 pub struct ProjectInfo {
@@ -76,6 +103,180 @@ pub struct ProjectInfo {
     pub bonded_lp_amount: u64,
     /// Vested amount
     pub vested_amount: u64,
+
+    /*************************** Milestones *************************/
+
+    /// Milestone table replacing the interval release when set
+    pub milestone_table: Pubkey,
+
+    /*************************** Accounting *************************/
+
+    /// Amount of project tokens paid out of the vault to positions
+    pub withdrawn_amount: u64,
+
+    /*************************** Bumps *************************/
+
+    /// Canonical bump of the project info
+    pub bump: u8,
+    /// Canonical bump of the token vault, used to sign transfers out of it
+    pub vault_bump: u8,
+}
+
+impl ProjectInfo {
+    /// Current discount in bips, moving between the min and max discount as the project tokens get vested.
+    /// A project whose tokens were all revoked counts as fully vested.
+    pub fn discount_rate(&self) -> u64 {
+        let discount_range = self.max_discount - self.min_discout;
+        let vested_range = (discount_range * self.vested_amount)
+            .checked_div(self.token_amount)
+            .unwrap_or(discount_range);
+        match self.discount_mode {
+            1 => self.min_discout + vested_range,
+            2 => self.max_discount - vested_range,
+            _ => 0,
+        }
+    }
+
+    /// Amount of project tokens vested for bonding `lp_amount` at the current discounted price.
+    /// Only the amount is rounded, down in the protocol's favor.
+    pub fn bond_vesting_amount(&self, lp_amount: u64, token_decimals: u8, lp_decimals: u8) -> Result<u64> {
+        // price in lp tokens per project token, exact for prices in `ACCURACY` and discounts in bips
+        let price = Decimal::from_scaled(self.price, ACCURACY).ok_or(BondError::InvalidPrice)?;
+        let discount = Decimal::from_bps(self.discount_rate()).ok_or(BondError::InvalidPrice)?;
+        let new_price = Decimal::one()
+            .checked_sub(discount)
+            .and_then(|rate| price.checked_mul_rounded(rate, Rounding::Up))
+            .ok_or(BondError::InvalidPrice)?;
+        // a zero price fails the division
+        Ok(new_price
+            .div_amount_in_base_units(lp_amount, token_decimals, lp_decimals, Rounding::Down)
+            .ok_or(BondError::InvalidPrice)?)
+    }
+
+    /// Prices and accounts a bond of `lp_amount` at `now`, returning the position it opens for `bonder`.
+    /// The caller sets the bump and transfers the lp tokens.
+    pub fn bond_position(
+        &mut self,
+        project_info: Pubkey,
+        bonder: Pubkey,
+        lp_amount: u64,
+        token_decimals: u8,
+        lp_decimals: u8,
+        now: u64,
+    ) -> Result<VestingInfo> {
+        let vesting_amount = self.bond_vesting_amount(lp_amount, token_decimals, lp_decimals)?;
+        self.record_bond(lp_amount, vesting_amount)?;
+        Ok(VestingInfo {
+            total_amount: vesting_amount,
+            start_time: now,
+            bonder,
+            owner: bonder,
+            project_info,
+            ..Default::default()
+        })
+    }
+
+    /// Project tokens promised to positions and not yet paid out; the vault holds at least this much.
+    pub fn outstanding_amount(&self) -> u64 {
+        self.vested_amount - self.withdrawn_amount
+    }
+
+    /// Accounts tokens paid out of the vault.
+    pub fn record_withdrawal(&mut self, amount: u64) {
+        self.withdrawn_amount += amount;
+    }
+
+    /// Accounts a new bond, failing when the project tokens are oversold.
+    pub fn record_bond(&mut self, lp_amount: u64, vesting_amount: u64) -> Result<()> {
+        self.bonded_lp_amount += lp_amount;
+        self.vested_amount += vesting_amount;
+
+        if self.vested_amount > self.token_amount {
+            return Err(ProgramError::Custom(10000).into());
+        }
+        Ok(())
+    }
+
+    /// Loads the project's milestone table, passed as the first remaining account when the project uses one.
+    pub fn load_milestone_table<'info>(&self, accounts: &[AccountInfo<'info>]) -> Result<Option<Account<'info, MilestoneTable>>> {
+        if self.milestone_table == Pubkey::default() {
+            return Ok(None);
+        }
+        let account_info = accounts.first().ok_or(BondError::MissingMilestoneTable)?;
+        if account_info.key() != self.milestone_table {
+            return Err(BondError::MissingMilestoneTable.into());
+        }
+        Ok(Some(Account::try_from(account_info)?))
+    }
+}
+
+#[derive(Default, Clone, AnchorSerialize, AnchorDeserialize)]
+pub struct Milestone {
+    /// Offset from the vesting start or unix timestamp, depending on the table's time mode
+    pub time: u64,
+    /// Percent of tokens unlocked in total once `time` is reached, in bips
+    pub cumulative_unlock: u64,
+}
+
+#[account]
+#[derive(Default)]
+pub struct MilestoneTable {
+    /// Project using this table
+    pub project_info: Pubkey,
+    /// 1 -> milestone times are offsets from the vesting start, 2 -> milestone times are unix timestamps
+    pub time_mode: u64,
+    /// Unlock milestones sorted by time
+    pub milestones: Vec<Milestone>,
+}
+
+impl MilestoneTable {
+    /// Checks that milestones are sorted by time, never decrease and end at 100%.
+    pub fn is_valid(&self) -> bool {
+        if self.time_mode != 1 && self.time_mode != 2 {
+            return false;
+        }
+        if self.milestones.is_empty() || self.milestones.len() > MAX_MILESTONES {
+            return false;
+        }
+        for pair in self.milestones.windows(2) {
+            if pair[1].time < pair[0].time || pair[1].cumulative_unlock < pair[0].cumulative_unlock {
+                return false;
+            }
+        }
+        self.milestones[self.milestones.len() - 1].cumulative_unlock == BIPS
+    }
+
+    /// Unlocked percent in bips at `now`, linearly interpolated between milestones.
+    /// Two milestones sharing the same time describe a step.
+    pub fn unlocked_bips(&self, start_time: u64, now: u64) -> u64 {
+        let time = match self.time_mode {
+            1 => {
+                if now < start_time {
+                    return 0;
+                }
+                now - start_time
+            }
+            _ => now,
+        };
+
+        let mut previous: Option<&Milestone> = None;
+        for milestone in self.milestones.iter() {
+            if time < milestone.time {
+                return match previous {
+                    Some(previous) => {
+                        previous.cumulative_unlock
+                            + (milestone.cumulative_unlock - previous.cumulative_unlock)
+                            * (time - previous.time)
+                            / (milestone.time - previous.time)
+                    }
+                    None => 0,
+                };
+            }
+            previous = Some(milestone);
+        }
+
+        previous.map(|milestone| milestone.cumulative_unlock).unwrap_or(0)
+    }
 }
 
 #[account]
@@ -100,4 +301,603 @@ pub struct VestingInfo {
     pub withdrawn_amount: u64,
     /// Start time of vesting
     pub start_time: u64,
+    /// Wallet that bonded the position, part of the vesting info seeds
+    pub bonder: Pubkey,
+    /// Wallet allowed to withdraw the position
+    pub owner: Pubkey,
+    /// Mint of the token representing the position, if tokenized. Its holder is allowed to withdraw the position.
+    pub position_mint: Pubkey,
+    /// Wallet allowed to claim the position into token accounts of the owner
+    pub claim_delegate: Pubkey,
+    /// Whether the project owner can revoke the unvested part, set for grants
+    pub revocable: bool,
+    /// Project info the position was bonded in and whose vault pays it out.
+    /// Default for positions created before it was stored, until migrated with `migrate_vesting`.
+    pub project_info: Pubkey,
+    /// Canonical bump of the vesting info
+    pub bump: u8,
+}
+
+/// Position behind a position mint, derived from the mint so that holders and marketplaces can resolve it.
+/// The amount is read from the vesting info and the unlock terms from the project info.
+#[account]
+#[derive(Default)]
+pub struct PositionInfo {
+    /// Vesting info the position token represents
+    pub vesting_info: Pubkey,
+    /// Project info the position was bonded in
+    pub project_info: Pubkey,
+    /// Wallet that bonded the position, part of the vesting info seeds
+    pub bonder: Pubkey,
+    /// Bond id of the position, part of the vesting info seeds
+    pub bond_id: u64,
+    /// Canonical bump of the position info
+    pub bump: u8,
+}
+
+impl VestingInfo {
+    /// Whether `signer` may claim the position into a token account owned by `recipient`.
+    /// The owner may claim anywhere, the claim delegate only into accounts of the owner.
+    pub fn can_claim(&self, bonder: &Pubkey, signer: &Pubkey, recipient: &Pubkey) -> bool {
+        let owner = self.current_owner(bonder);
+        if *signer == owner {
+            return true;
+        }
+        self.claim_delegate != Pubkey::default() && *signer == self.claim_delegate && *recipient == owner
+    }
+
+    pub fn is_tokenized(&self) -> bool {
+        self.position_mint != Pubkey::default()
+    }
+
+    /// Wallet that bonded the position, part of its address.
+    /// Positions created before it was stored can not have been transferred, so `owner` bonded them.
+    pub fn stored_bonder_or(&self, owner: &Pubkey) -> Pubkey {
+        if self.bonder == Pubkey::default() {
+            *owner
+        } else {
+            self.bonder
+        }
+    }
+
+    /// Owner of the position. Positions created before ownership was tracked belong to their bonder.
+    pub fn current_owner(&self, bonder: &Pubkey) -> Pubkey {
+        if self.owner == Pubkey::default() {
+            *bonder
+        } else {
+            self.owner
+        }
+    }
+
+    /// Hands the position to `new_owner`, keeping the bonder its address derives from.
+    /// The claim delegate was chosen by the previous owner and is cleared.
+    pub fn transfer(&mut self, bonder: &Pubkey, new_owner: &Pubkey) {
+        self.bonder = *bonder;
+        self.owner = *new_owner;
+        self.claim_delegate = Pubkey::default();
+    }
+
+    /// Checks that `owner` may merge the position, bonded by `bonder`, within `project_info`.
+    /// Revocable grants are never merged, so that `revoke` keeps reaching exactly the granted tokens.
+    pub fn check_mergeable(&self, bonder: &Pubkey, owner: &Pubkey, project_info: &Pubkey) -> Result<()> {
+        if self.current_owner(bonder) != *owner {
+            return Err(BondError::NotVestingOwner.into());
+        }
+        if self.is_tokenized() {
+            return Err(BondError::TokenizedPosition.into());
+        }
+        if self.revocable {
+            return Err(BondError::RevocablePosition.into());
+        }
+        if self.project_info != *project_info {
+            return Err(BondError::WrongProject.into());
+        }
+        Ok(())
+    }
+
+    /// Checks that `owner` may withdraw the position, bonded by `bonder`, in a batch within `project_info`.
+    /// Tokenized positions are withdrawn by the holder of their token instead.
+    pub fn check_batch_withdrawable(&self, bonder: &Pubkey, owner: &Pubkey, project_info: &Pubkey) -> Result<()> {
+        if self.current_owner(bonder) != *owner {
+            return Err(BondError::NotVestingOwner.into());
+        }
+        if self.is_tokenized() {
+            return Err(BondError::TokenizedPosition.into());
+        }
+        if self.project_info != *project_info {
+            return Err(BondError::WrongProject.into());
+        }
+        Ok(())
+    }
+
+    /// Amount of tokens vested at `now` under the project's vesting schedule.
+    pub fn vested_amount(&self, project_info: &ProjectInfo, now: u64, accuracy: u64, milestone_table: Option<&MilestoneTable>) -> u64 {
+        let lock_end_time = self.start_time + project_info.lock_period;
+        let vesting_end_time = lock_end_time + project_info.vesting_period;
+
+        let vesting_unlock_amount =
+            scaled_share(self.total_amount, project_info.instant_unlock, accuracy);
+
+        if self.start_time == 0 || self.total_amount == 0 {
+            return 0;
+        }
+
+        if let Some(milestone_table) = milestone_table {
+            let unlocked_bips = milestone_table.unlocked_bips(self.start_time, now);
+            let vested_amount = Decimal::from_bps(unlocked_bips).unwrap()
+                .mul_amount(self.total_amount, Rounding::Down).unwrap();
+            return vested_amount.max(self.withdrawn_amount).min(self.total_amount);
+        }
+
+        // a merged position may have withdrawn more than its own schedule unlocked so far
+        if now <= lock_end_time {
+            return vesting_unlock_amount.max(self.withdrawn_amount).min(self.total_amount);
+        }
+
+        if now > vesting_end_time {
+            return self.total_amount;
+        }
+
+        // the unlocked rates are summed before converting to an amount so that
+        // truncation happens once instead of once per interval
+        let released_rate = ((now - lock_end_time) / project_info.release_interval)
+            .saturating_mul(project_info.release_rate);
+        let unlocked_rate = project_info.instant_unlock
+            .saturating_add(project_info.initial_unlock)
+            .saturating_add(released_rate)
+            .min(accuracy);
+        let mut vested_amount = scaled_share(self.total_amount, unlocked_rate, accuracy);
+        let withdrawn_amount = self.withdrawn_amount;
+
+        if withdrawn_amount > vested_amount {
+            vested_amount = withdrawn_amount;
+        }
+
+        if vested_amount > self.total_amount {
+            vested_amount = self.total_amount;
+        }
+        vested_amount
+    }
+
+    /// Address of the vesting info derived with its stored bump, sparing the bump search.
+    pub fn address(&self, project_token: &Pubkey, bonder: &Pubkey, bond_id: u64) -> Option<Pubkey> {
+        Pubkey::create_program_address(&[
+                project_token.as_ref(),
+                bonder.as_ref(),
+                VESTING_INFO_SEED.as_ref(),
+                bond_id.to_string().as_bytes(),
+                &[self.bump],
+            ], &crate::ID).ok()
+    }
+
+    /// Marks the position withdrawn up to `vested_amount` and returns the amount to pay out.
+    /// Once fully vested this is exactly what is left of `total_amount`, rounding dust included.
+    pub fn record_withdrawal(&mut self, vested_amount: u64) -> u64 {
+        let withdrawable_amount = self.claimable_amount(vested_amount);
+        self.withdrawn_amount = self.withdrawn_amount.max(vested_amount);
+        withdrawable_amount
+    }
+
+    /// Sets the amount already paid out of a position migrated from before withdrawals were stored,
+    /// returning the part not recorded yet.
+    pub fn migrate_withdrawn_amount(&mut self, withdrawn_amount: u64) -> Result<u64> {
+        if withdrawn_amount < self.withdrawn_amount || withdrawn_amount > self.total_amount {
+            return Err(BondError::InvalidWithdrawnAmount.into());
+        }
+        let paid_out_amount = withdrawn_amount - self.withdrawn_amount;
+        self.withdrawn_amount = withdrawn_amount;
+        Ok(paid_out_amount)
+    }
+
+    /// Amount left to withdraw out of `vested_amount`.
+    pub fn claimable_amount(&self, vested_amount: u64) -> u64 {
+        vested_amount.saturating_sub(self.withdrawn_amount)
+    }
+}
+
+/// Share of `amount` for a rate scaled by `accuracy`, rounded down as it is paid out.
+fn scaled_share(amount: u64, rate: u64, accuracy: u64) -> u64 {
+    Decimal::from_scaled(rate, accuracy).unwrap()
+        .mul_amount(amount, Rounding::Down).unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn error_code(result: Result<()>) -> Option<u32> {
+        match result {
+            Err(Error::AnchorError(error)) => Some(error.error_code_number),
+            _ => None,
+        }
+    }
+
+    fn position(owner: Pubkey, project_info: Pubkey) -> VestingInfo {
+        VestingInfo {
+            total_amount: 1000,
+            start_time: 1,
+            bonder: owner,
+            owner,
+            project_info,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn bond_amount_rounds_once() {
+        // 1e-9 lp per project token of 10 decimals is 1e-19 per base unit, below the decimal precision
+        let project_info = ProjectInfo {
+            price: 1,
+            token_amount: 1,
+            ..Default::default()
+        };
+        assert_eq!(project_info.bond_vesting_amount(1, 10, 0).ok(), Some(10_000_000_000_000_000_000));
+        assert_eq!(project_info.bond_vesting_amount(3, 9, 0).ok(), Some(3_000_000_000_000_000_000));
+
+        let project_info = ProjectInfo {
+            price: 3,
+            token_amount: 1,
+            ..Default::default()
+        };
+        assert_eq!(project_info.bond_vesting_amount(1, 10, 0).ok(), Some(3_333_333_333_333_333_333));
+    }
+
+    #[test]
+    fn discount_of_an_emptied_project_does_not_divide_by_zero() {
+        let mut project_info = ProjectInfo {
+            min_discout: 100,
+            max_discount: 500,
+            discount_mode: 1,
+            ..Default::default()
+        };
+        assert_eq!(project_info.discount_rate(), 500);
+        project_info.discount_mode = 2;
+        assert_eq!(project_info.discount_rate(), 100);
+    }
+
+    #[test]
+    fn bond_position_records_the_bond() {
+        // 0.5 lp per project token, both of 9 decimals
+        let mut project_info = ProjectInfo {
+            price: ACCURACY / 2,
+            token_amount: 3000,
+            ..Default::default()
+        };
+        let bonder = Pubkey::new_unique();
+        let project_info_key = Pubkey::new_unique();
+        let vesting_info = project_info.bond_position(project_info_key, bonder, 1000, 9, 9, 42).unwrap();
+        assert_eq!(vesting_info.total_amount, 2000);
+        assert_eq!(vesting_info.start_time, 42);
+        assert_eq!(vesting_info.current_owner(&vesting_info.bonder), bonder);
+        assert_eq!(vesting_info.project_info, project_info_key);
+        assert_eq!(project_info.bonded_lp_amount, 1000);
+        assert_eq!(project_info.vested_amount, 2000);
+
+        assert!(project_info.bond_position(project_info_key, bonder, 1000, 9, 9, 42).is_err());
+    }
+
+    #[test]
+    fn zero_bond_price_is_an_error() {
+        let project_info = ProjectInfo {
+            token_amount: 1,
+            ..Default::default()
+        };
+        assert_eq!(
+            error_code(project_info.bond_vesting_amount(1, 9, 9).map(|_| ())),
+            Some(BondError::InvalidPrice.into())
+        );
+    }
+
+    #[test]
+    fn bonded_position_can_be_merged() {
+        let owner = Pubkey::new_unique();
+        let project_info = Pubkey::new_unique();
+        let vesting_info = position(owner, project_info);
+        assert!(vesting_info.check_mergeable(&owner, &owner, &project_info).is_ok());
+    }
+
+    #[test]
+    fn revocable_grant_can_not_be_merged() {
+        let owner = Pubkey::new_unique();
+        let project_info = Pubkey::new_unique();
+        let mut grant = position(owner, project_info);
+        grant.revocable = true;
+        assert_eq!(
+            error_code(grant.check_mergeable(&owner, &owner, &project_info)),
+            Some(BondError::RevocablePosition.into())
+        );
+    }
+
+    #[test]
+    fn transferred_position_is_merged_by_its_new_owner() {
+        let bonder = Pubkey::new_unique();
+        let new_owner = Pubkey::new_unique();
+        let project_info = Pubkey::new_unique();
+        let mut vesting_info = position(bonder, project_info);
+        vesting_info.owner = new_owner;
+        assert!(vesting_info.check_mergeable(&bonder, &new_owner, &project_info).is_ok());
+        assert_eq!(
+            error_code(vesting_info.check_mergeable(&bonder, &bonder, &project_info)),
+            Some(BondError::NotVestingOwner.into())
+        );
+    }
+
+    #[test]
+    fn migration_records_the_stated_withdrawals() {
+        let mut vesting_info = position(Pubkey::new_unique(), Pubkey::default());
+        assert_eq!(
+            error_code(vesting_info.migrate_withdrawn_amount(1001).map(|_| ())),
+            Some(BondError::InvalidWithdrawnAmount.into())
+        );
+        assert_eq!(vesting_info.migrate_withdrawn_amount(400).ok(), Some(400));
+        assert_eq!(vesting_info.withdrawn_amount, 400);
+        assert_eq!(
+            error_code(vesting_info.migrate_withdrawn_amount(300).map(|_| ())),
+            Some(BondError::InvalidWithdrawnAmount.into())
+        );
+    }
+
+    #[test]
+    fn merged_partly_withdrawn_position_does_not_underflow() {
+        // 10% unlocks at bond time, the rest after a 100s lock
+        let project_info = ProjectInfo {
+            instant_unlock: ACCURACY / 10,
+            lock_period: 100,
+            vesting_period: 100,
+            release_interval: 1,
+            release_rate: ACCURACY / 100,
+            ..Default::default()
+        };
+        let owner = Pubkey::new_unique();
+        // a position bonded at 1 and withdrawn for 600 at 161 merged with a fresh one bonded at 161:
+        // the merged position restarts at 81 and is locked again
+        let mut merged = position(owner, Pubkey::new_unique());
+        merged.total_amount = 2000;
+        merged.withdrawn_amount = 600;
+        merged.start_time = 81;
+
+        let vested_amount = merged.vested_amount(&project_info, 161, ACCURACY, None);
+        assert_eq!(vested_amount, 600);
+        assert_eq!(merged.claimable_amount(vested_amount), 0);
+        assert_eq!(merged.record_withdrawal(vested_amount), 0);
+        assert_eq!(merged.withdrawn_amount, 600);
+
+        // once the unlock catches up only the new part is paid out
+        let vested_amount = merged.vested_amount(&project_info, 221, ACCURACY, None);
+        assert_eq!(vested_amount, 2000 * 50 / 100);
+        assert_eq!(merged.record_withdrawal(vested_amount), 400);
+        assert_eq!(merged.withdrawn_amount, 1000);
+    }
+
+    fn milestone(time: u64, cumulative_unlock: u64) -> Milestone {
+        Milestone { time, cumulative_unlock }
+    }
+
+    #[test]
+    fn milestones_must_be_sorted_and_end_fully_unlocked() {
+        let mut table = MilestoneTable {
+            time_mode: 1,
+            milestones: vec![milestone(0, 1_000), milestone(100, 10_000)],
+            ..Default::default()
+        };
+        assert!(table.is_valid());
+
+        table.milestones = vec![milestone(100, 1_000), milestone(0, 10_000)];
+        assert!(!table.is_valid());
+        table.milestones = vec![milestone(0, 5_000), milestone(100, 1_000), milestone(200, 10_000)];
+        assert!(!table.is_valid());
+        table.milestones = vec![milestone(0, 1_000), milestone(100, 9_999)];
+        assert!(!table.is_valid());
+        table.milestones = vec![];
+        assert!(!table.is_valid());
+
+        table.milestones = vec![milestone(100, 10_000)];
+        table.time_mode = 3;
+        assert!(!table.is_valid());
+    }
+
+    #[test]
+    fn milestones_interpolate_linearly_with_steps() {
+        // nothing before 100s, 20% at 100s rising to 50% at 200s, a step to 80% and 100% at 300s
+        let table = MilestoneTable {
+            time_mode: 1,
+            milestones: vec![
+                milestone(100, 2_000),
+                milestone(200, 5_000),
+                milestone(200, 8_000),
+                milestone(300, 10_000),
+            ],
+            ..Default::default()
+        };
+        assert_eq!(table.unlocked_bips(1_000, 999), 0);
+        assert_eq!(table.unlocked_bips(1_000, 1_099), 0);
+        assert_eq!(table.unlocked_bips(1_000, 1_100), 2_000);
+        assert_eq!(table.unlocked_bips(1_000, 1_150), 3_500);
+        assert_eq!(table.unlocked_bips(1_000, 1_199), 4_970);
+        assert_eq!(table.unlocked_bips(1_000, 1_200), 8_000);
+        assert_eq!(table.unlocked_bips(1_000, 1_250), 9_000);
+        assert_eq!(table.unlocked_bips(1_000, 5_000), 10_000);
+
+        // unix timestamps ignore the start of the position
+        let table = MilestoneTable {
+            time_mode: 2,
+            ..table
+        };
+        assert_eq!(table.unlocked_bips(1_000, 150), 3_500);
+    }
+
+    #[test]
+    fn milestone_table_replaces_the_interval_release() {
+        let project_info = ProjectInfo {
+            lock_period: 1_000,
+            ..Default::default()
+        };
+        let table = MilestoneTable {
+            time_mode: 1,
+            milestones: vec![milestone(0, 0), milestone(100, 10_000)],
+            ..Default::default()
+        };
+        let vesting_info = position(Pubkey::new_unique(), Pubkey::new_unique());
+        // still locked under the project's schedule, a quarter unlocked under the table
+        assert_eq!(vesting_info.vested_amount(&project_info, 26, ACCURACY, None), 0);
+        assert_eq!(vesting_info.vested_amount(&project_info, 26, ACCURACY, Some(&table)), 250);
+        assert_eq!(vesting_info.vested_amount(&project_info, 1_000, ACCURACY, Some(&table)), 1000);
+    }
+
+    #[test]
+    fn batch_withdraw_checks_every_position() {
+        let owner = Pubkey::new_unique();
+        let project_info = Pubkey::new_unique();
+        let vesting_info = position(owner, project_info);
+        assert!(vesting_info.check_batch_withdrawable(&owner, &owner, &project_info).is_ok());
+        assert_eq!(
+            error_code(vesting_info.check_batch_withdrawable(&owner, &Pubkey::new_unique(), &project_info)),
+            Some(BondError::NotVestingOwner.into())
+        );
+        assert_eq!(
+            error_code(vesting_info.check_batch_withdrawable(&owner, &owner, &Pubkey::new_unique())),
+            Some(BondError::WrongProject.into())
+        );
+
+        let mut tokenized = position(owner, project_info);
+        tokenized.position_mint = Pubkey::new_unique();
+        assert_eq!(
+            error_code(tokenized.check_batch_withdrawable(&owner, &owner, &project_info)),
+            Some(BondError::TokenizedPosition.into())
+        );
+    }
+
+    #[test]
+    fn batch_withdraw_pays_each_position_once() {
+        // linear release of 1% per second after the bond
+        let mut project_info = ProjectInfo {
+            vesting_period: 100,
+            release_interval: 1,
+            release_rate: ACCURACY / 100,
+            ..Default::default()
+        };
+        let owner = Pubkey::new_unique();
+        let mut positions = vec![position(owner, Pubkey::default()), position(owner, Pubkey::default())];
+        positions[1].start_time = 51;
+
+        let mut withdraw_all = |positions: &mut Vec<VestingInfo>, now: u64| -> u64 {
+            let withdrawable_amount = positions.iter_mut()
+                .map(|vesting_info| {
+                    let vested_amount = vesting_info.vested_amount(&project_info, now, ACCURACY, None);
+                    vesting_info.record_withdrawal(vested_amount)
+                })
+                .sum();
+            project_info.record_withdrawal(withdrawable_amount);
+            withdrawable_amount
+        };
+        assert_eq!(withdraw_all(&mut positions, 76), 750 + 250);
+        assert_eq!(withdraw_all(&mut positions, 76), 0);
+        assert_eq!(withdraw_all(&mut positions, 1_000), 250 + 750);
+        assert_eq!(project_info.withdrawn_amount, 2000);
+    }
+
+    #[test]
+    fn transfer_moves_ownership_and_keeps_the_address() {
+        let bonder = Pubkey::new_unique();
+        let new_owner = Pubkey::new_unique();
+        let project_token = Pubkey::new_unique();
+        let (address, bump) = Pubkey::find_program_address(&[
+                project_token.as_ref(),
+                bonder.as_ref(),
+                VESTING_INFO_SEED.as_ref(),
+                b"0".as_ref(),
+            ], &crate::ID);
+        let mut vesting_info = position(bonder, Pubkey::new_unique());
+        vesting_info.bump = bump;
+        vesting_info.claim_delegate = Pubkey::new_unique();
+
+        vesting_info.transfer(&bonder, &new_owner);
+        assert_eq!(vesting_info.current_owner(&bonder), new_owner);
+        assert_eq!(vesting_info.stored_bonder_or(&new_owner), bonder);
+        assert_eq!(vesting_info.address(&project_token, &bonder, 0), Some(address));
+        assert_eq!(vesting_info.claim_delegate, Pubkey::default());
+        assert!(vesting_info.can_claim(&bonder, &new_owner, &Pubkey::new_unique()));
+        assert!(!vesting_info.can_claim(&bonder, &bonder, &bonder));
+    }
+
+    #[test]
+    fn legacy_position_transfer_records_its_bonder() {
+        // positions bonded before the bonder and owner were stored belong to the wallet they were bonded by
+        let bonder = Pubkey::new_unique();
+        let new_owner = Pubkey::new_unique();
+        let mut vesting_info = VestingInfo {
+            total_amount: 1000,
+            start_time: 1,
+            ..Default::default()
+        };
+        assert_eq!(vesting_info.current_owner(&bonder), bonder);
+
+        vesting_info.transfer(&bonder, &new_owner);
+        assert_eq!(vesting_info.stored_bonder_or(&new_owner), bonder);
+        assert_eq!(vesting_info.current_owner(&bonder), new_owner);
+    }
+
+    #[test]
+    fn owner_claims_anywhere_and_delegate_only_to_the_owner() {
+        let owner = Pubkey::new_unique();
+        let delegate = Pubkey::new_unique();
+        let stranger = Pubkey::new_unique();
+        let mut vesting_info = position(owner, Pubkey::new_unique());
+        vesting_info.claim_delegate = delegate;
+
+        assert!(vesting_info.can_claim(&owner, &owner, &owner));
+        assert!(vesting_info.can_claim(&owner, &owner, &stranger));
+        assert!(vesting_info.can_claim(&owner, &delegate, &owner));
+        assert!(!vesting_info.can_claim(&owner, &delegate, &delegate));
+        assert!(!vesting_info.can_claim(&owner, &stranger, &owner));
+    }
+
+    #[test]
+    fn cleared_delegate_can_not_claim() {
+        let owner = Pubkey::new_unique();
+        let vesting_info = position(owner, Pubkey::new_unique());
+        // the default key is the cleared delegate, not a signer that may claim
+        assert!(!vesting_info.can_claim(&owner, &Pubkey::default(), &owner));
+    }
+
+    #[test]
+    fn quotes_match_bonding_and_withdrawing() {
+        let mut project_info = ProjectInfo {
+            price: ACCURACY / 3,
+            token_amount: 1_000_000,
+            vesting_period: 100,
+            release_interval: 1,
+            release_rate: ACCURACY / 100,
+            ..Default::default()
+        };
+        let quoted_amount = project_info.bond_vesting_amount(1000, 9, 9).unwrap();
+        let mut vesting_info = project_info
+            .bond_position(Pubkey::new_unique(), Pubkey::new_unique(), 1000, 9, 9, 1)
+            .unwrap();
+        assert_eq!(quoted_amount, 3000);
+        assert_eq!(vesting_info.total_amount, quoted_amount);
+
+        let vested_amount = vesting_info.vested_amount(&project_info, 34, ACCURACY, None);
+        let quoted_claimable = vesting_info.claimable_amount(vested_amount);
+        assert_eq!(quoted_claimable, 990);
+        assert_eq!(vesting_info.record_withdrawal(vested_amount), quoted_claimable);
+        assert_eq!(vesting_info.claimable_amount(vested_amount), 0);
+    }
+
+    #[test]
+    fn vesting_status_is_three_little_endian_amounts() {
+        // programs reading the view over CPI may decode the return data by hand
+        let status = VestingStatus {
+            total_amount: 1,
+            vested_amount: 2,
+            withdrawn_amount: 3,
+        };
+        let data = status.try_to_vec().unwrap();
+        assert_eq!(data.len(), 24);
+        assert_eq!(data[..8], 1u64.to_le_bytes());
+        assert_eq!(data[8..16], 2u64.to_le_bytes());
+        assert_eq!(data[16..], 3u64.to_le_bytes());
+        let decoded = VestingStatus::try_from_slice(&data).unwrap();
+        assert_eq!(decoded.withdrawn_amount, 3);
+    }
 }
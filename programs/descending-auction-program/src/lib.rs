@@ -2,8 +2,11 @@ use std::convert::TryFrom;
 use std::mem::size_of;
 
 use anchor_lang::prelude::*;
+use anchor_lang::solana_program::program::{invoke, set_return_data};
+use anchor_lang::solana_program::system_instruction;
+use anchor_lang::Discriminator;
 use anchor_spl::token::{self, CloseAccount, Mint, Token, TokenAccount, Transfer};
-use spl_math::{precise_number::PreciseNumber, uint::U256};
+use bonding_math::{Decimal, Rounding, LN_2};
 
 #[cfg(feature = "local-testing")]
 declare_id!("CwuWwv57X9Yerfhkh9oEDJzr1qgyDFYr2mkyZ3HH8jjJ");
@@ -12,6 +15,12 @@ declare_id!("CwuWwv57X9Yerfhkh9oEDJzr1qgyDFYr2mkyZ3HH8jjJ");
 declare_id!("Fg6PaFpoGXkYsidMpWTK6W2BeZ7FEfcYkg476zPFsLnS");
 
 pub const AUCTION_PREFIX: &[u8] = b"auction";
+pub const PURCHASE_RECEIPT_PREFIX: &[u8] = b"purchase_receipt";
+/// Layout of the `Auction` account written by this program, see `migrate_auction`
+pub const AUCTION_VERSION: u8 = 1;
+/// Half-life, in seconds, of the legacy price decay by a factor of 1.00005 per second
+/// (ln 2 / ln 1.00005), see `migrate_auction`
+pub const LEGACY_HALF_LIFE: i64 = 13_863;
 
 #[program]
 pub mod descending_auction_program {
@@ -26,21 +35,27 @@ pub mod descending_auction_program {
     //
     /// Sets up a new auction
     ///
+    /// Without `end_timestamp` the auction runs until its pool is sold out or its authority ends it.
+    ///
     /// preconditions:
     ///  - `start_timestamp < end_timestamp`
     ///  - `floor_price < ceil_price`
     ///  - `floor_price > 0`
+    ///  - the durations of `decay_curve` are positive
+    ///  - a linear or stepwise `decay_curve` has an `end_timestamp`
+    ///  - `price_hold_duration >= 0`
     #[allow(clippy::too_many_arguments)]
     pub fn initialize_auction(
         ctx: Context<InitializeAuction>,
         start_timestamp: i64,
-        end_timestamp: i64, // TODO: should this be an option type to represent open-ended auction?
-        ceil_price: u64,    // represented as # of payment tokens per purchase token
+        end_timestamp: Option<i64>, // `None` for an open-ended auction
+        ceil_price: u64,            // represented as # of payment tokens per purchase token
         floor_price: u64,
-        price_hold_duration: i64, // in seconds  // TODO: delete this
-        auction_authority_bump: u8,
-        auction_pool_bump: u8,
-    ) -> ProgramResult {
+        decay_curve: DecayCurve,
+        pricing_mode: PricingMode,
+        price_hold_duration: i64, // in seconds
+        price_bump_bps: u64,      // raise of the price after a purchase, in basis points
+    ) -> Result<()> {
         // get given accounts
         let auction = &mut ctx.accounts.auction;
         let authority = &ctx.accounts.authority;
@@ -50,7 +65,7 @@ pub mod descending_auction_program {
         let auction_pool = &ctx.accounts.auction_pool;
 
         // assert preconditions of the instruction
-        if end_timestamp <= start_timestamp {
+        if end_timestamp.map_or(false, |end_timestamp| end_timestamp <= start_timestamp) {
             msg!("Ending time of auction cannot be before starting time");
             return Err(AuctionError::InvalidAuctionTimestamps.into());
         }
@@ -58,6 +73,18 @@ pub mod descending_auction_program {
             msg!("Auction price has to be none zero");
             return Err(AuctionError::InvalidAuctionPrice.into());
         }
+        if !decay_curve.is_valid() {
+            msg!("Decay curve durations have to be positive");
+            return Err(AuctionError::InvalidDecayCurve.into());
+        }
+        if end_timestamp.is_none() && !matches!(decay_curve, DecayCurve::Exponential { .. }) {
+            msg!("Open-ended auction requires an exponential decay curve");
+            return Err(AuctionError::InvalidDecayCurve.into());
+        }
+        if price_hold_duration < 0 {
+            msg!("Price hold duration cannot be negative");
+            return Err(AuctionError::InvalidPriceHoldDuration.into());
+        }
 
         // populate auction account
         auction.authority = authority.key();
@@ -70,10 +97,26 @@ pub mod descending_auction_program {
         auction.ceil_price = ceil_price;
         auction.floor_price = floor_price;
         auction.price_hold_duration = price_hold_duration;
-        auction.auction_authority_bump = auction_authority_bump;
-        auction.auction_pool_bump = auction_pool_bump;
+        auction.auction_authority_bump = *ctx.bumps.get("auction_authority").unwrap();
+        auction.auction_pool_bump = *ctx.bumps.get("auction_pool").unwrap();
         auction.last_purchase_timestamp = None;
         auction.last_purchase_price = ceil_price;
+        auction.decay_curve = decay_curve;
+        auction.price_bump_bps = price_bump_bps;
+        auction.pricing_mode = pricing_mode;
+        auction.total_deposited = 0;
+        auction.total_sold = 0;
+        auction.total_raised = 0;
+        auction.purchase_count = 0;
+        auction.max_per_buyer = None;
+        auction.max_per_tx = None;
+        auction.min_purchase = 0;
+        auction.vesting = None;
+        auction.total_claimed = 0;
+        auction.ended = false;
+        auction.guardian = None;
+        auction.cancelled = false;
+        auction.version = AUCTION_VERSION;
 
         Ok(())
     }
@@ -84,9 +127,9 @@ pub mod descending_auction_program {
     ///  - `auction.start_timestamp < end_timestamp`
     ///  - auction is pending
     #[access_control(ctx.accounts.auction.assert_auction_state(AuctionState::Pending))]
-    pub fn update_end_time(ctx: Context<UpdateEndTime>, end_timestamp: i64) -> ProgramResult {
+    pub fn update_end_time(ctx: Context<UpdateEndTime>, end_timestamp: i64) -> Result<()> {
         let auction = &mut ctx.accounts.auction;
-        auction.end_timestamp = end_timestamp;
+        auction.end_timestamp = Some(end_timestamp);
 
         Ok(())
     }
@@ -98,7 +141,7 @@ pub mod descending_auction_program {
     ///  - `current_timestamp < start_timestamp`
     ///  - auction is pending
     #[access_control(ctx.accounts.auction.assert_auction_state(AuctionState::Pending))]
-    pub fn update_start_time(ctx: Context<UpdateStartTime>, start_timestamp: i64) -> ProgramResult {
+    pub fn update_start_time(ctx: Context<UpdateStartTime>, start_timestamp: i64) -> Result<()> {
         let current_timestamp = Clock::get()?.unix_timestamp;
 
         if start_timestamp <= current_timestamp {
@@ -118,7 +161,7 @@ pub mod descending_auction_program {
     ///  - `auction.floor_price < ceil_price`
     ///  - auction is pending
     #[access_control(ctx.accounts.auction.assert_auction_state(AuctionState::Pending))]
-    pub fn update_ceil_price(ctx: Context<UpdateCeilPrice>, ceil_price: u64) -> ProgramResult {
+    pub fn update_ceil_price(ctx: Context<UpdateCeilPrice>, ceil_price: u64) -> Result<()> {
         let auction = &mut ctx.accounts.auction;
         auction.ceil_price = ceil_price;
 
@@ -132,13 +175,112 @@ pub mod descending_auction_program {
     ///  - `floor_price > 0`
     ///  - auction is pending
     #[access_control(ctx.accounts.auction.assert_auction_state(AuctionState::Pending))]
-    pub fn update_floor_price(ctx: Context<UpdateFloorPrice>, floor_price: u64) -> ProgramResult {
+    pub fn update_floor_price(ctx: Context<UpdateFloorPrice>, floor_price: u64) -> Result<()> {
         let auction = &mut ctx.accounts.auction;
         auction.floor_price = floor_price;
 
         Ok(())
     }
 
+    /// Updates the purchase limits of an auction, in sale token
+    ///
+    /// preconditions:
+    ///  - `min_purchase <= max_per_tx` and `min_purchase <= max_per_buyer`
+    ///  - auction is pending
+    #[access_control(ctx.accounts.auction.assert_auction_state(AuctionState::Pending))]
+    pub fn update_purchase_limits(
+        ctx: Context<UpdatePurchaseLimits>,
+        max_per_buyer: Option<u64>,
+        max_per_tx: Option<u64>,
+        min_purchase: u64,
+    ) -> Result<()> {
+        let auction = &mut ctx.accounts.auction;
+        auction.max_per_buyer = max_per_buyer;
+        auction.max_per_tx = max_per_tx;
+        auction.min_purchase = min_purchase;
+
+        Ok(())
+    }
+
+    /// Sets or removes the vesting of the purchased sale tokens
+    ///
+    /// With a vesting, purchased sale tokens stay in the auction pool and are released to each buyer
+    /// through `claim`.
+    ///
+    /// preconditions:
+    ///  - `0 <= vesting.cliff_duration <= vesting.duration`
+    ///  - `vesting.duration > 0`
+    ///  - auction is pending
+    #[access_control(ctx.accounts.auction.assert_auction_state(AuctionState::Pending))]
+    pub fn update_vesting(
+        ctx: Context<UpdateVesting>,
+        vesting: Option<VestingSchedule>,
+    ) -> Result<()> {
+        let auction = &mut ctx.accounts.auction;
+        auction.vesting = vesting;
+
+        Ok(())
+    }
+
+    /// Sets or removes the guardian who can cancel an auction besides its authority
+    ///
+    /// preconditions:
+    ///  - auction is pending
+    #[access_control(ctx.accounts.auction.assert_auction_state(AuctionState::Pending))]
+    pub fn update_guardian(ctx: Context<UpdateGuardian>, guardian: Option<Pubkey>) -> Result<()> {
+        let auction = &mut ctx.accounts.auction;
+        auction.guardian = guardian;
+
+        Ok(())
+    }
+
+    /// Cancels a pending or in progress auction, stopping purchases immediately
+    ///
+    /// The authority can then recover the remaining pool through `close_auction`.
+    ///
+    /// preconditions:
+    ///  - auction is pending or in progress
+    ///  - signed by the authority or the guardian of the auction
+    #[access_control(ctx.accounts.auction.assert_auction_state_not_finished())]
+    pub fn cancel_auction(ctx: Context<CancelAuction>) -> Result<()> {
+        let auction = &mut ctx.accounts.auction;
+        msg!("Cancelling auction");
+        auction.cancelled = true;
+
+        Ok(())
+    }
+
+    /// Ends an auction in progress; meant for open-ended auctions
+    ///
+    /// preconditions:
+    ///  - auction is in progress
+    #[access_control(ctx.accounts.auction.assert_auction_state(AuctionState::InProgress))]
+    pub fn end_auction(ctx: Context<EndAuction>) -> Result<()> {
+        let auction = &mut ctx.accounts.auction;
+        auction.ended = true;
+
+        Ok(())
+    }
+
+    /// Switches an auction to a uniform clearing price
+    ///
+    /// Payments are escrowed; once the auction ends, the price of the final fill becomes the price
+    /// for every buyer and the difference is refunded through `claim_refund`.
+    ///
+    /// preconditions:
+    ///  - auction is pending
+    #[access_control(ctx.accounts.auction.assert_auction_state(AuctionState::Pending))]
+    pub fn enable_uniform_price(ctx: Context<EnableUniformPrice>) -> Result<()> {
+        let auction = &mut ctx.accounts.auction;
+        let payment_escrow = &ctx.accounts.payment_escrow;
+
+        auction.uniform_price = true;
+        auction.payment_escrow = payment_escrow.key();
+        auction.payment_escrow_bump = *ctx.bumps.get("payment_escrow").unwrap();
+
+        Ok(())
+    }
+
     /// Deposits a given amount of sale tokens into an auction pool
     ///
     /// preconditions:
@@ -147,8 +289,9 @@ pub mod descending_auction_program {
     pub fn deposit_to_auction_pool(
         ctx: Context<DepositToAuctionPool>,
         deposit_amount: u64,
-    ) -> ProgramResult {
+    ) -> Result<()> {
         // get given accounts
+        let auction = &mut ctx.accounts.auction;
         let authority = &ctx.accounts.authority;
         let source_account = &ctx.accounts.source_account;
         let auction_pool = &ctx.accounts.auction_pool;
@@ -163,15 +306,66 @@ pub mod descending_auction_program {
         let deposit_ctx = CpiContext::new(token_program.to_account_info(), deposit_accounts);
         token::transfer(deposit_ctx, deposit_amount)?;
 
+        auction.total_deposited = auction
+            .total_deposited
+            .checked_add(deposit_amount)
+            .ok_or(AuctionError::InternalError)?;
+
+        Ok(())
+    }
+
+    /// Refund the unsold sale tokens of a pending, ended or cancelled auction, keeping it open
+    ///
+    /// The purchased sale tokens not claimed yet stay in the auction pool; see `close_auction` once
+    /// they have all been claimed.
+    ///
+    /// preconditions:
+    ///  - auction is pending, ended or cancelled
+    #[access_control(ctx.accounts.auction.assert_auction_state_not_in_progress())]
+    pub fn withdraw_unsold(ctx: Context<WithdrawUnsold>) -> Result<()> {
+        let auction = &ctx.accounts.auction;
+        let auction_authority = &ctx.accounts.auction_authority;
+        let auction_pool = &ctx.accounts.auction_pool;
+        let destination_account = &ctx.accounts.destination_account;
+        let token_program = &ctx.accounts.token_program;
+
+        let refund_amount = auction.get_available_amount(auction_pool.amount);
+        if refund_amount > 0 {
+            msg!("Transferring unsold sale tokens");
+            let refund_accounts = Transfer {
+                from: auction_pool.to_account_info(),
+                to: destination_account.to_account_info(),
+                authority: auction_authority.to_account_info(),
+            };
+            let auction_authority_seeds = &[
+                AUCTION_PREFIX,
+                &auction.key().to_bytes(),
+                &[auction.auction_authority_bump],
+            ];
+            let auction_authority_signer = &[&auction_authority_seeds[..]];
+            let refund_ctx = CpiContext::new_with_signer(
+                token_program.to_account_info(),
+                refund_accounts,
+                auction_authority_signer,
+            );
+            token::transfer(refund_ctx, refund_amount)?;
+        }
+
+        // withdrawn tokens are no longer for sale
+        let auction = &mut ctx.accounts.auction;
+        auction.total_deposited = auction.total_deposited.saturating_sub(refund_amount);
+
         Ok(())
     }
 
-    /// Close a pending or ended auction and refund the sale token back
+    /// Close a pending, ended or cancelled auction and refund the sale token back
     ///
     /// preconditions:
-    ///  - auction is pending or ended
+    ///  - auction is pending, ended or cancelled
+    ///  - with a uniform clearing price, the proceeds have been withdrawn
+    ///  - with a vesting, every purchased sale token has been claimed; see `withdraw_unsold` until then
     #[access_control(ctx.accounts.auction.assert_auction_state_not_in_progress())]
-    pub fn close_auction(ctx: Context<CloseAuction>) -> ProgramResult {
+    pub fn close_auction(ctx: Context<CloseAuction>) -> Result<()> {
         let auction = &ctx.accounts.auction;
         let auction_authority = &ctx.accounts.auction_authority;
         let auction_pool = &ctx.accounts.auction_pool;
@@ -218,6 +412,54 @@ pub mod descending_auction_program {
         Ok(())
     }
 
+    /// Rewrites an auction created before open-ended auctions in the current layout
+    ///
+    /// The account grows to `Auction::LEN`, the authority paying the extra rent; the price keeps
+    /// decaying as before, on an exponential curve of `LEGACY_HALF_LIFE`, and the other settings
+    /// added since keep the defaults of `initialize_auction`.
+    ///
+    /// preconditions:
+    ///  - the auction is in the `LegacyAuction` layout
+    pub fn migrate_auction(ctx: Context<MigrateAuction>) -> Result<()> {
+        let auction_info = &ctx.accounts.auction;
+        let authority = &ctx.accounts.authority;
+        let system_program = &ctx.accounts.system_program;
+
+        let legacy = {
+            let data = auction_info.try_borrow_data()?;
+            if data.len() != LegacyAuction::LEN || data[..8] != Auction::discriminator() {
+                return Err(AuctionError::InvalidAuctionLayout.into());
+            }
+            LegacyAuction::deserialize(&mut &data[8..])
+                .map_err(|_| AuctionError::InvalidAuctionLayout)?
+        };
+        if legacy.authority != authority.key() {
+            return Err(AuctionError::InvalidAuctionAuthority.into());
+        }
+
+        let rent_due = Rent::get()?
+            .minimum_balance(Auction::LEN)
+            .saturating_sub(auction_info.lamports());
+        if rent_due > 0 {
+            msg!("Funding the rent of the migrated auction");
+            invoke(
+                &system_instruction::transfer(&authority.key(), &auction_info.key(), rent_due),
+                &[
+                    authority.to_account_info(),
+                    auction_info.to_account_info(),
+                    system_program.to_account_info(),
+                ],
+            )?;
+        }
+        auction_info.realloc(Auction::LEN, true)?;
+
+        let auction = Auction::from(legacy);
+        let mut data = auction_info.try_borrow_mut_data()?;
+        auction.try_serialize(&mut &mut data[..])?;
+
+        Ok(())
+    }
+
     //
     // User-facing instructions:
     //
@@ -225,6 +467,7 @@ pub mod descending_auction_program {
     ///
     /// preconditions:
     ///  - auction is in progress
+    ///  - the purchase amount is not zero
     // TODO:
     //  - accept expected price and slippage tolerance
     //  - if the slippage is higher than the given tolerance, cancel the tx with an error
@@ -236,33 +479,36 @@ pub mod descending_auction_program {
         purchase_amount: u64, // in sale token (whole number mutiple of the smallest unit)
         expected_payment: u64, // in payment token
         slippage_tolerance: u64, // permille as unsigned integer (min: 0; max:1000)
-    ) -> ProgramResult {
+    ) -> Result<()> {
         let current_timestamp = Clock::get()?.unix_timestamp;
 
         // get given accounts
-        let auction = &mut ctx.accounts.auction;
-        let auction_authority = &ctx.accounts.auction_authority;
+        let auction = &ctx.accounts.auction;
         let sale_mint = &ctx.accounts.sale_mint;
         let auction_pool = &ctx.accounts.auction_pool;
-        let payment_destination = &ctx.accounts.payment_destination;
-        let buyer = &ctx.accounts.buyer;
-        let payment_source = &ctx.accounts.payment_source;
-        let sale_destination = &ctx.accounts.sale_destination;
 
-        let token_program = &ctx.accounts.token_program;
+        if purchase_amount == 0 {
+            return Err(AuctionError::PurchaseAmountTooLow.into());
+        }
+        if auction.get_available_amount(auction_pool.amount) < purchase_amount {
+            msg!("Auction pool does not hold the purchase amount");
+            return Err(AuctionError::AuctionPoolBalanceTooLow.into());
+        }
 
-        // TODO: decide which token is going to be the base token of the trade; we can:
-        //  -> calculate the amount of payment tokens to accept based on the amount of sale tokens
-        //  -  calculate the amount of sale tokens to dispense based on the amount of payment tokens
+        // NOTE: the sale token is the base token of the trade here; the amount of payment tokens to accept is
+        // calculated based on the amount of sale tokens (`purchase_exact_payment` does the opposite)
         msg!("calculate auction invoice");
         // NOTE:
         //  - `purchase_amount` is the amount of unit sale token for purchase
         //  - `payment_amount` is the (least) amount of unit payment token suffices to be exchanged to purchase_amount
-        let payment_amount = auction.get_payment_amount(purchase_amount, sale_mint.decimals)?;
-
-        // TODO: validation for the trade:
-        // 1. make sure the price is non-zero
-        // 2. TODO: do i need to double check the pool balance?
+        let current_price = auction
+            .get_price_at(current_timestamp)
+            .ok_or(AuctionError::InternalError)?;
+        let payment_amount = Auction::get_payment_amount_at_price(
+            current_price,
+            purchase_amount,
+            sale_mint.decimals,
+        )?;
 
         // check if the calculated price exceeds the given slippage
         if payment_amount > expected_payment {
@@ -276,19 +522,207 @@ pub mod descending_auction_program {
             }
         }
 
-        // accept the payment
-        msg!("Transferring payment token");
-        let payment_accounts = Transfer {
-            from: payment_source.to_account_info(),
-            to: payment_destination.to_account_info(),
-            authority: buyer.to_account_info(),
+        let purchase_receipt_bump = *ctx.bumps.get("purchase_receipt").unwrap();
+        ctx.accounts.settle_purchase(
+            purchase_amount,
+            payment_amount,
+            current_price,
+            current_timestamp,
+            purchase_receipt_bump,
+        )
+    }
+
+    /// Purchase as much token from an auction as a given payment amount buys at current time
+    ///
+    /// The purchase amount is rounded down and capped at the balance of the auction pool and at the
+    /// purchase limits of the buyer; only the payment for the purchased amount is charged.
+    ///
+    /// preconditions:
+    ///  - auction is in progress
+    ///  - the purchased amount is at least `min_purchase_amount` and not zero
+    #[access_control(ctx.accounts.auction.assert_auction_state(AuctionState::InProgress))]
+    pub fn purchase_exact_payment(
+        ctx: Context<Purchase>,
+        payment_amount: u64,      // in payment token
+        min_purchase_amount: u64, // in sale token
+    ) -> Result<()> {
+        let current_timestamp = Clock::get()?.unix_timestamp;
+
+        // get given accounts
+        let auction = &ctx.accounts.auction;
+        let sale_mint = &ctx.accounts.sale_mint;
+        let auction_pool = &ctx.accounts.auction_pool;
+        let purchase_receipt = &ctx.accounts.purchase_receipt;
+
+        msg!("calculate auction invoice");
+        let current_price = auction
+            .get_price_at(current_timestamp)
+            .ok_or(AuctionError::InternalError)?;
+        let purchase_amount = Auction::get_purchase_amount_at_price(
+            current_price,
+            payment_amount,
+            sale_mint.decimals,
+        )?
+        .min(auction.get_available_amount(auction_pool.amount))
+        .min(auction.get_purchase_allowance(purchase_receipt.purchased_amount));
+        if purchase_amount == 0 || purchase_amount < min_purchase_amount {
+            msg!("Payment buys {} sale token", purchase_amount);
+            return Err(AuctionError::PurchaseAmountTooLow.into());
+        }
+        let payment_amount = Auction::get_payment_amount_at_price(
+            current_price,
+            purchase_amount,
+            sale_mint.decimals,
+        )?;
+
+        let purchase_receipt_bump = *ctx.bumps.get("purchase_receipt").unwrap();
+        ctx.accounts.settle_purchase(
+            purchase_amount,
+            payment_amount,
+            current_price,
+            current_timestamp,
+            purchase_receipt_bump,
+        )
+    }
+
+    /// Refund a buyer the difference between its payments and the clearing price
+    ///
+    /// The refund is sent to a payment token account of the buyer; anyone can trigger it.
+    ///
+    /// preconditions:
+    ///  - auction is ended or cancelled and uses a uniform clearing price
+    ///  - the purchase receipt has not been refunded yet
+    #[access_control(ctx.accounts.auction.assert_auction_state_finished())]
+    pub fn claim_refund(ctx: Context<ClaimRefund>) -> Result<()> {
+        let auction = &mut ctx.accounts.auction;
+        let auction_authority = &ctx.accounts.auction_authority;
+        let sale_mint = &ctx.accounts.sale_mint;
+        let payment_escrow = &ctx.accounts.payment_escrow;
+        let purchase_receipt = &mut ctx.accounts.purchase_receipt;
+        let refund_account = &ctx.accounts.refund_account;
+        let token_program = &ctx.accounts.token_program;
+
+        let clearing_payment = Auction::get_payment_amount_at_price(
+            auction.clearing_price,
+            purchase_receipt.purchased_amount,
+            sale_mint.decimals,
+        )?;
+        let refund_amount = purchase_receipt
+            .paid_amount
+            .saturating_sub(clearing_payment);
+
+        if refund_amount > 0 {
+            msg!("Transferring refund");
+            let refund_accounts = Transfer {
+                from: payment_escrow.to_account_info(),
+                to: refund_account.to_account_info(),
+                authority: auction_authority.to_account_info(),
+            };
+            let auction_authority_seeds = &[
+                AUCTION_PREFIX,
+                &auction.key().to_bytes(),
+                &[auction.auction_authority_bump],
+            ];
+            let auction_authority_signer = &[&auction_authority_seeds[..]];
+            let refund_ctx = CpiContext::new_with_signer(
+                token_program.to_account_info(),
+                refund_accounts,
+                auction_authority_signer,
+            );
+            token::transfer(refund_ctx, refund_amount)?;
+        }
+
+        purchase_receipt.refunded = true;
+        auction.pending_refunds = auction
+            .pending_refunds
+            .checked_sub(1)
+            .ok_or(AuctionError::InternalError)?;
+
+        Ok(())
+    }
+
+    /// Send the escrowed payments of a uniform clearing price auction to its payment destination
+    ///
+    /// Closes the payment escrow.
+    ///
+    /// preconditions:
+    ///  - auction is ended or cancelled and uses a uniform clearing price
+    ///  - every buyer has been refunded
+    #[access_control(ctx.accounts.auction.assert_auction_state_finished())]
+    pub fn withdraw_proceeds(ctx: Context<WithdrawProceeds>) -> Result<()> {
+        let auction = &mut ctx.accounts.auction;
+        let auction_authority = &ctx.accounts.auction_authority;
+        let payment_escrow = &ctx.accounts.payment_escrow;
+        let payment_destination = &ctx.accounts.payment_destination;
+        let authority = &ctx.accounts.authority;
+        let token_program = &ctx.accounts.token_program;
+
+        let auction_authority_seeds = &[
+            AUCTION_PREFIX,
+            &auction.key().to_bytes(),
+            &[auction.auction_authority_bump],
+        ];
+        let auction_authority_signer = &[&auction_authority_seeds[..]];
+
+        if payment_escrow.amount > 0 {
+            msg!("Transferring proceeds");
+            let proceeds_accounts = Transfer {
+                from: payment_escrow.to_account_info(),
+                to: payment_destination.to_account_info(),
+                authority: auction_authority.to_account_info(),
+            };
+            let proceeds_ctx = CpiContext::new_with_signer(
+                token_program.to_account_info(),
+                proceeds_accounts,
+                auction_authority_signer,
+            );
+            token::transfer(proceeds_ctx, payment_escrow.amount)?;
+        }
+
+        // close payment escrow token account and refund lamports
+        let close_accounts = CloseAccount {
+            account: payment_escrow.to_account_info(),
+            destination: authority.to_account_info(),
+            authority: auction_authority.to_account_info(),
         };
-        let payment_ctx = CpiContext::new(token_program.to_account_info(), payment_accounts);
-        token::transfer(payment_ctx, payment_amount)?;
+        let close_ctx = CpiContext::new_with_signer(
+            token_program.to_account_info(),
+            close_accounts,
+            auction_authority_signer,
+        );
+        token::close_account(close_ctx)?;
+
+        auction.proceeds_withdrawn = true;
+
+        Ok(())
+    }
+
+    /// Claim the vested sale tokens purchased by a buyer
+    ///
+    /// preconditions:
+    ///  - auction has a vesting
+    ///  - some purchased sale tokens have vested and not been claimed yet
+    pub fn claim(ctx: Context<Claim>) -> Result<()> {
+        let current_timestamp = Clock::get()?.unix_timestamp;
+
+        let auction = &mut ctx.accounts.auction;
+        let auction_authority = &ctx.accounts.auction_authority;
+        let auction_pool = &ctx.accounts.auction_pool;
+        let purchase_receipt = &mut ctx.accounts.purchase_receipt;
+        let sale_destination = &ctx.accounts.sale_destination;
+        let token_program = &ctx.accounts.token_program;
+
+        let vesting = auction.vesting.ok_or(AuctionError::AuctionNotVesting)?;
+        let claim_amount = vesting
+            .get_vested_amount(purchase_receipt.purchased_amount, current_timestamp)
+            .saturating_sub(purchase_receipt.claimed_amount);
+        if claim_amount == 0 {
+            msg!("No vested sale tokens to claim");
+            return Err(AuctionError::NothingToClaim.into());
+        }
 
-        // dispense the sale token to the buyer
         msg!("Transferring sale token");
-        let sale_accounts = Transfer {
+        let claim_accounts = Transfer {
             from: auction_pool.to_account_info(),
             to: sale_destination.to_account_info(),
             authority: auction_authority.to_account_info(),
@@ -299,16 +733,42 @@ pub mod descending_auction_program {
             &[auction.auction_authority_bump],
         ];
         let auction_authority_signer = &[&auction_authority_seeds[..]];
-        let sale_ctx = CpiContext::new_with_signer(
+        let claim_ctx = CpiContext::new_with_signer(
             token_program.to_account_info(),
-            sale_accounts,
+            claim_accounts,
             auction_authority_signer,
         );
-        token::transfer(sale_ctx, purchase_amount)?;
+        token::transfer(claim_ctx, claim_amount)?;
 
-        // update the on-chain state for this purchase
-        msg!("Update timestamp for last purchase");
-        auction.last_purchase_timestamp = Some(current_timestamp);
+        purchase_receipt.claimed_amount = purchase_receipt
+            .claimed_amount
+            .checked_add(claim_amount)
+            .ok_or(AuctionError::InternalError)?;
+        auction.total_claimed = auction
+            .total_claimed
+            .checked_add(claim_amount)
+            .ok_or(AuctionError::InternalError)?;
+
+        Ok(())
+    }
+
+    //
+    // Read-only instructions:
+    //
+    /// Quote the payment amount for a given purchasing amount at current time
+    ///
+    /// The payment amount (in payment token, little-endian u64) is set as return data;
+    /// meant to be used with transaction simulation.
+    ///
+    /// preconditions:
+    ///  - auction is in progress
+    #[access_control(ctx.accounts.auction.assert_auction_state(AuctionState::InProgress))]
+    pub fn quote_purchase(ctx: Context<QuotePurchase>, purchase_amount: u64) -> Result<()> {
+        let auction = &ctx.accounts.auction;
+        let sale_mint = &ctx.accounts.sale_mint;
+
+        let payment_amount = auction.get_payment_amount(purchase_amount, sale_mint.decimals)?;
+        set_return_data(&payment_amount.to_le_bytes());
 
         Ok(())
     }
@@ -318,15 +778,6 @@ pub mod descending_auction_program {
 // instructions.rs
 //
 #[derive(Accounts)]
-#[instruction(
-    _start_timestamp: i64,
-    _end_timestamp: i64,
-    _ceil_price: u64,
-    _floor_price: u64,
-    _price_hold_duration: i64,
-    auction_authority_bump: u8,
-    auction_pool_bump: u8,
-)]
 pub struct InitializeAuction<'info> {
     /// The `Auction` account to be initialize
     #[account(
@@ -337,13 +788,15 @@ pub struct InitializeAuction<'info> {
     pub auction: Account<'info, Auction>,
 
     /// (PDA) The authority assigned to each auction that controls auction pool
+    /// CHECK: PDA checked by its seeds, only signs for the token accounts of the auction
     #[account(
         seeds = [AUCTION_PREFIX, &auction.key().to_bytes()],
-        bump = auction_authority_bump,
+        bump,
     )]
     pub auction_authority: AccountInfo<'info>,
 
     /// The authority that controls the auction being initialized
+    /// CHECK: any signer can set up an auction
     #[account(
         mut @ AuctionError::AuthorityNotMutable,
         signer @ AuctionError::AuthorityNotSigner,
@@ -369,7 +822,7 @@ pub struct InitializeAuction<'info> {
         token::mint = sale_mint,
         token::authority = auction_authority,
         seeds = [AUCTION_PREFIX, &auction.key().to_bytes(), &sale_mint.key().to_bytes()],
-        bump = auction_pool_bump,
+        bump,
     )]
     pub auction_pool: Account<'info, TokenAccount>,
 
@@ -390,6 +843,7 @@ pub struct UpdateEndTime<'info> {
     pub auction: Account<'info, Auction>,
 
     /// The authority that controls the provided auction
+    /// CHECK: checked against the auction by `has_one`
     #[account(
         signer @ AuctionError::AuthorityNotSigner
     )]
@@ -403,11 +857,12 @@ pub struct UpdateStartTime<'info> {
     #[account(
         mut,
         has_one = authority @ AuctionError::InvalidAuctionAuthority,
-        constraint = auction.end_timestamp > start_timestamp  @ AuctionError::InvalidAuctionTimestamps,
+        constraint = auction.end_timestamp.map_or(true, |end_timestamp| end_timestamp > start_timestamp) @ AuctionError::InvalidAuctionTimestamps,
     )]
     pub auction: Account<'info, Auction>,
 
     /// The authority that controls the provided auction
+    /// CHECK: checked against the auction by `has_one`
     #[account(
         signer @ AuctionError::AuthorityNotSigner,
     )]
@@ -426,6 +881,7 @@ pub struct UpdateCeilPrice<'info> {
     pub auction: Account<'info, Auction>,
 
     /// The authority that controls the provided auction
+    /// CHECK: checked against the auction by `has_one`
     #[account(
         signer @ AuctionError::AuthorityNotSigner,
     )]
@@ -444,6 +900,7 @@ pub struct UpdateFloorPrice<'info> {
     pub auction: Account<'info, Auction>,
 
     /// The authority that controls the provided auction
+    /// CHECK: checked against the auction by `has_one`
     #[account(
         signer @ AuctionError::AuthorityNotSigner,
     )]
@@ -451,41 +908,221 @@ pub struct UpdateFloorPrice<'info> {
 }
 
 #[derive(Accounts)]
-#[instruction(deposit_amount: u64)]
-pub struct DepositToAuctionPool<'info> {
-    /// The auction to deposit the sale tokens to
-    // NOTE: if we want to allow depositing to an `InProgress` auction then the constraint has to change
+#[instruction(max_per_buyer: Option<u64>, max_per_tx: Option<u64>, min_purchase: u64)]
+pub struct UpdatePurchaseLimits<'info> {
+    /// The auction to modify
     #[account(
+        mut,
         has_one = authority @ AuctionError::InvalidAuctionAuthority,
+        constraint = max_per_tx.map_or(true, |max_per_tx| min_purchase <= max_per_tx) @ AuctionError::InvalidPurchaseLimits,
+        constraint = max_per_buyer.map_or(true, |max_per_buyer| min_purchase <= max_per_buyer) @ AuctionError::InvalidPurchaseLimits,
     )]
     pub auction: Account<'info, Auction>,
 
-    /// (PDA) The pool to deposit sale tokens; controled by the auction authority
+    /// The authority that controls the provided auction
+    /// CHECK: checked against the auction by `has_one`
     #[account(
-        mut,
-        seeds = [AUCTION_PREFIX, &auction.key().to_bytes(), &auction.sale_mint.to_bytes()],
-        bump = auction.auction_pool_bump,
-        constraint = auction_pool.owner == auction_authority.key() @ AuctionError::AuctionPoolNotOwnedByAuction,
+        signer @ AuctionError::AuthorityNotSigner,
     )]
-    pub auction_pool: Account<'info, TokenAccount>,
+    pub authority: AccountInfo<'info>,
+}
 
-    /// (PDA) The authority assigned to the auction
+#[derive(Accounts)]
+#[instruction(vesting: Option<VestingSchedule>)]
+pub struct UpdateVesting<'info> {
+    /// The auction to modify
     #[account(
-        seeds = [AUCTION_PREFIX, &auction.key().to_bytes()],
-        bump = auction.auction_authority_bump,
+        mut,
+        has_one = authority @ AuctionError::InvalidAuctionAuthority,
+        constraint = vesting.map_or(true, |vesting| vesting.is_valid()) @ AuctionError::InvalidVestingSchedule,
     )]
-    pub auction_authority: AccountInfo<'info>,
+    pub auction: Account<'info, Auction>,
 
     /// The authority that controls the provided auction
+    /// CHECK: checked against the auction by `has_one`
     #[account(
         signer @ AuctionError::AuthorityNotSigner,
     )]
     pub authority: AccountInfo<'info>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateGuardian<'info> {
+    /// The auction to modify
+    #[account(
+        mut,
+        has_one = authority @ AuctionError::InvalidAuctionAuthority,
+    )]
+    pub auction: Account<'info, Auction>,
+
+    /// The authority that controls the provided auction
+    /// CHECK: checked against the auction by `has_one`
+    #[account(
+        signer @ AuctionError::AuthorityNotSigner,
+    )]
+    pub authority: AccountInfo<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CancelAuction<'info> {
+    /// The auction to cancel
+    #[account(
+        mut,
+        constraint = auction.can_cancel(&canceller.key()) @ AuctionError::InvalidAuctionAuthority,
+    )]
+    pub auction: Account<'info, Auction>,
+
+    /// The authority or the guardian of the provided auction
+    /// CHECK: checked against the auction by `can_cancel`
+    #[account(
+        signer @ AuctionError::AuthorityNotSigner,
+    )]
+    pub canceller: AccountInfo<'info>,
+}
+
+#[derive(Accounts)]
+pub struct EndAuction<'info> {
+    /// The auction to end
+    #[account(
+        mut,
+        has_one = authority @ AuctionError::InvalidAuctionAuthority,
+    )]
+    pub auction: Account<'info, Auction>,
+
+    /// The authority that controls the provided auction
+    /// CHECK: checked against the auction by `has_one`
+    #[account(
+        signer @ AuctionError::AuthorityNotSigner,
+    )]
+    pub authority: AccountInfo<'info>,
+}
+
+#[derive(Accounts)]
+pub struct EnableUniformPrice<'info> {
+    /// The auction to modify
+    #[account(
+        mut,
+        has_one = authority @ AuctionError::InvalidAuctionAuthority,
+    )]
+    pub auction: Account<'info, Auction>,
+
+    /// (PDA) The authority assigned to the auction
+    /// CHECK: PDA checked by its seeds, only signs for the token accounts of the auction
+    #[account(
+        seeds = [AUCTION_PREFIX, &auction.key().to_bytes()],
+        bump = auction.auction_authority_bump,
+    )]
+    pub auction_authority: AccountInfo<'info>,
+
+    /// The authority that controls the provided auction
+    /// CHECK: checked against the auction by `has_one`
+    #[account(
+        mut @ AuctionError::AuthorityNotMutable,
+        signer @ AuctionError::AuthorityNotSigner,
+    )]
+    pub authority: AccountInfo<'info>,
+
+    /// The payment token mint
+    #[account(
+        constraint = payment_mint.key() == auction.payment_mint,
+    )]
+    pub payment_mint: Account<'info, Mint>,
+
+    /// (PDA) The escrow to hold payments until the clearing price is known; controled by the auction authority
+    #[account(
+        init,
+        payer = authority,
+        token::mint = payment_mint,
+        token::authority = auction_authority,
+        seeds = [AUCTION_PREFIX, &auction.key().to_bytes(), &payment_mint.key().to_bytes()],
+        bump,
+    )]
+    pub payment_escrow: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+#[instruction(deposit_amount: u64)]
+pub struct DepositToAuctionPool<'info> {
+    /// The auction to deposit the sale tokens to
+    // NOTE: if we want to allow depositing to an `InProgress` auction then the constraint has to change
+    #[account(
+        mut,
+        has_one = authority @ AuctionError::InvalidAuctionAuthority,
+    )]
+    pub auction: Account<'info, Auction>,
+
+    /// (PDA) The pool to deposit sale tokens; controled by the auction authority
+    #[account(
+        mut,
+        seeds = [AUCTION_PREFIX, &auction.key().to_bytes(), &auction.sale_mint.to_bytes()],
+        bump = auction.auction_pool_bump,
+        constraint = auction_pool.owner == auction_authority.key() @ AuctionError::AuctionPoolNotOwnedByAuction,
+    )]
+    pub auction_pool: Account<'info, TokenAccount>,
+
+    /// (PDA) The authority assigned to the auction
+    /// CHECK: PDA checked by its seeds, only signs for the token accounts of the auction
+    #[account(
+        seeds = [AUCTION_PREFIX, &auction.key().to_bytes()],
+        bump = auction.auction_authority_bump,
+    )]
+    pub auction_authority: AccountInfo<'info>,
+
+    /// The authority that controls the provided auction
+    /// CHECK: checked against the auction by `has_one`
+    #[account(
+        signer @ AuctionError::AuthorityNotSigner,
+    )]
+    pub authority: AccountInfo<'info>,
+
+    /// The sale token account that will fund the auction pool
+    #[account(mut)]
+    pub source_account: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct WithdrawUnsold<'info> {
+    /// The auction to refund the unsold sale tokens of
+    #[account(
+        mut,
+        has_one = authority @ AuctionError::InvalidAuctionAuthority,
+    )]
+    pub auction: Account<'info, Auction>,
+
+    /// (PDA) The pool that holds sale tokens; controled by the auction authority
+    #[account(
+        mut,
+        seeds = [AUCTION_PREFIX, &auction.key().to_bytes(), &auction.sale_mint.to_bytes()],
+        bump = auction.auction_pool_bump,
+        constraint = auction_pool.owner == auction_authority.key() @ AuctionError::AuctionPoolNotOwnedByAuction,
+    )]
+    pub auction_pool: Account<'info, TokenAccount>,
+
+    /// (PDA) The authority assigned to the auction
+    /// CHECK: PDA checked by its seeds, only signs for the token accounts of the auction
+    #[account(
+        seeds = [AUCTION_PREFIX, &auction.key().to_bytes()],
+        bump = auction.auction_authority_bump,
+    )]
+    pub auction_authority: AccountInfo<'info>,
+
+    /// The authority that controls the provided auction
+    /// CHECK: checked against the auction by `has_one`
+    #[account(
+        signer @ AuctionError::AuthorityNotSigner,
+    )]
+    pub authority: AccountInfo<'info>,
+
+    /// The sale token account to refund to
+    #[account(mut)]
+    pub destination_account: Account<'info, TokenAccount>,
 
-    /// The sale token account that will fund the auction pool
-    #[account(mut)]
-    pub source_account: Account<'info, TokenAccount>,
-
     pub token_program: Program<'info, Token>,
 }
 
@@ -495,6 +1132,8 @@ pub struct CloseAuction<'info> {
     #[account(
         mut,
         has_one = authority @ AuctionError::InvalidAuctionAuthority,
+        constraint = !auction.uniform_price || auction.proceeds_withdrawn @ AuctionError::ProceedsNotWithdrawn,
+        constraint = auction.get_unclaimed_amount() == 0 @ AuctionError::SaleTokensUnclaimed,
         close = authority,
     )]
     pub auction: Account<'info, Auction>,
@@ -509,6 +1148,7 @@ pub struct CloseAuction<'info> {
     pub auction_pool: Account<'info, TokenAccount>,
 
     /// (PDA) The authority assigned to the auction
+    /// CHECK: PDA checked by its seeds, only signs for the token accounts of the auction
     #[account(
         seeds = [AUCTION_PREFIX, &auction.key().to_bytes()],
         bump = auction.auction_authority_bump,
@@ -516,7 +1156,9 @@ pub struct CloseAuction<'info> {
     pub auction_authority: AccountInfo<'info>,
 
     /// The authority that controls the provided auction
+    /// CHECK: checked against the auction by `has_one`
     #[account(
+        mut @ AuctionError::AuthorityNotMutable,
         signer @ AuctionError::AuthorityNotSigner,
     )]
     pub authority: AccountInfo<'info>,
@@ -529,13 +1171,31 @@ pub struct CloseAuction<'info> {
 }
 
 #[derive(Accounts)]
-#[instruction(purchase_amount: u64)]
+pub struct MigrateAuction<'info> {
+    /// The auction in the legacy layout
+    /// CHECK: can not be deserialized in the legacy layout; checked by `migrate_auction`
+    #[account(mut, owner = ID)]
+    pub auction: AccountInfo<'info>,
+
+    /// The authority that controls the provided auction; pays the rent of the larger account
+    /// CHECK: checked against the legacy auction by `migrate_auction`
+    #[account(
+        mut @ AuctionError::AuthorityNotMutable,
+        signer @ AuctionError::AuthorityNotSigner,
+    )]
+    pub authority: AccountInfo<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
 pub struct Purchase<'info> {
     /// The auction to purchase from
     #[account(mut)]
     pub auction: Account<'info, Auction>,
 
     /// (PDA) The auction authority to sign the sale token transfer
+    /// CHECK: PDA checked by its seeds, only signs for the token accounts of the auction
     #[account(
         seeds = [AUCTION_PREFIX, &auction.key().to_bytes()],
         bump = auction.auction_authority_bump,
@@ -555,22 +1215,32 @@ pub struct Purchase<'info> {
         bump = auction.auction_pool_bump,
         constraint = auction_pool.owner == auction_authority.key() @ AuctionError::AuctionPoolNotOwnedByAuction,
         constraint = auction_pool.mint == auction.sale_mint @ AuctionError::AuctionPoolWrongMint,
-        constraint = auction_pool.amount >= purchase_amount @ AuctionError::AuctionPoolBalanceTooLow,
     )]
     pub auction_pool: Box<Account<'info, TokenAccount>>,
 
-    /// The sale token account that the payment will be sent to
+    /// The payment token account that the payment will be sent to; the payment escrow with a uniform clearing price
     #[account(
         mut,
         constraint = payment_destination.mint == auction.payment_mint @ AuctionError::InvalidPaymentDestination,
-        constraint = payment_destination.key() == auction.payment_destination @ AuctionError::InvalidPaymentDestination,
+        constraint = payment_destination.key() == auction.get_payment_receiver() @ AuctionError::InvalidPaymentDestination,
     )]
     pub payment_destination: Account<'info, TokenAccount>,
 
     /// The buyer who signs the payment token transaction
-    #[account(signer)]
+    /// CHECK: any signer can purchase
+    #[account(mut, signer)]
     pub buyer: AccountInfo<'info>,
 
+    /// (PDA) The purchases of the buyer in the auction
+    #[account(
+        init_if_needed,
+        payer = buyer,
+        space = PurchaseReceipt::LEN,
+        seeds = [PURCHASE_RECEIPT_PREFIX, &auction.key().to_bytes(), &buyer.key().to_bytes()],
+        bump,
+    )]
+    pub purchase_receipt: Box<Account<'info, PurchaseReceipt>>,
+
     /// The buyer's payment token account to fund the purchase
     #[account(
         mut,
@@ -579,6 +1249,247 @@ pub struct Purchase<'info> {
     )]
     pub payment_source: Account<'info, TokenAccount>,
 
+    /// The buyer's sale token account to receive the sale token; unused with a vesting
+    #[account(
+        mut,
+        constraint = sale_destination.mint == auction.sale_mint @ AuctionError::InvalidSaleDestinationMint,
+    )]
+    pub sale_destination: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> Purchase<'info> {
+    /// Exchanges the payment for the sale token and updates the on-chain state for the purchase
+    fn settle_purchase(
+        &mut self,
+        purchase_amount: u64,
+        payment_amount: u64,
+        current_price: Decimal,
+        current_timestamp: i64,
+        purchase_receipt_bump: u8,
+    ) -> Result<()> {
+        self.auction
+            .assert_purchase_limits(purchase_amount, self.purchase_receipt.purchased_amount)?;
+
+        let auction_key = self.auction.key();
+        let token_program = &self.token_program;
+
+        // accept the payment
+        msg!("Transferring payment token");
+        let payment_accounts = Transfer {
+            from: self.payment_source.to_account_info(),
+            to: self.payment_destination.to_account_info(),
+            authority: self.buyer.to_account_info(),
+        };
+        let payment_ctx = CpiContext::new(token_program.to_account_info(), payment_accounts);
+        token::transfer(payment_ctx, payment_amount)?;
+
+        // dispense the sale token to the buyer, or keep it in the auction pool to be claimed
+        if self.auction.vesting.is_none() {
+            msg!("Transferring sale token");
+            let sale_accounts = Transfer {
+                from: self.auction_pool.to_account_info(),
+                to: self.sale_destination.to_account_info(),
+                authority: self.auction_authority.to_account_info(),
+            };
+            let auction_authority_seeds = &[
+                AUCTION_PREFIX,
+                &auction_key.to_bytes(),
+                &[self.auction.auction_authority_bump],
+            ];
+            let auction_authority_signer = &[&auction_authority_seeds[..]];
+            let sale_ctx = CpiContext::new_with_signer(
+                token_program.to_account_info(),
+                sale_accounts,
+                auction_authority_signer,
+            );
+            token::transfer(sale_ctx, purchase_amount)?;
+        } else {
+            msg!("Keeping sale token for vesting");
+        }
+
+        // update the on-chain state for this purchase
+        msg!("Update timestamp and price for last purchase");
+        let auction = &mut self.auction;
+        if auction.uniform_price {
+            // the price of the final fill; buyers who paid less after a price bump are not refunded
+            auction.clearing_price = current_price;
+        }
+        auction.last_purchase_timestamp = Some(current_timestamp);
+        auction.last_purchase_price = current_price
+            .to_u64(Rounding::Up)
+            .ok_or(AuctionError::InternalError)?;
+        auction.total_sold = auction
+            .total_sold
+            .checked_add(purchase_amount)
+            .ok_or(AuctionError::InternalError)?;
+        auction.total_raised = auction
+            .total_raised
+            .checked_add(payment_amount)
+            .ok_or(AuctionError::InternalError)?;
+        auction.purchase_count = auction
+            .purchase_count
+            .checked_add(1)
+            .ok_or(AuctionError::InternalError)?;
+
+        msg!("Record the purchase");
+        let purchase_receipt = &mut self.purchase_receipt;
+        if purchase_receipt.auction == Pubkey::default() {
+            purchase_receipt.auction = auction_key;
+            purchase_receipt.buyer = self.buyer.key();
+            purchase_receipt.bump = purchase_receipt_bump;
+            if auction.uniform_price {
+                auction.pending_refunds = auction
+                    .pending_refunds
+                    .checked_add(1)
+                    .ok_or(AuctionError::InternalError)?;
+            }
+        }
+        purchase_receipt.purchased_amount = purchase_receipt
+            .purchased_amount
+            .checked_add(purchase_amount)
+            .ok_or(AuctionError::InternalError)?;
+        purchase_receipt.paid_amount = purchase_receipt
+            .paid_amount
+            .checked_add(payment_amount)
+            .ok_or(AuctionError::InternalError)?;
+        purchase_receipt.last_purchase_timestamp = current_timestamp;
+
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct ClaimRefund<'info> {
+    /// The auction to refund from
+    #[account(
+        mut,
+        constraint = auction.uniform_price @ AuctionError::AuctionNotUniformPrice,
+    )]
+    pub auction: Account<'info, Auction>,
+
+    /// (PDA) The auction authority to sign the refund transfer
+    /// CHECK: PDA checked by its seeds, only signs for the token accounts of the auction
+    #[account(
+        seeds = [AUCTION_PREFIX, &auction.key().to_bytes()],
+        bump = auction.auction_authority_bump,
+    )]
+    pub auction_authority: AccountInfo<'info>,
+
+    /// The sale token mint
+    #[account(
+        constraint = sale_mint.key() == auction.sale_mint,
+    )]
+    pub sale_mint: Account<'info, Mint>,
+
+    /// (PDA) The escrow holding the payments
+    #[account(
+        mut,
+        seeds = [AUCTION_PREFIX, &auction.key().to_bytes(), &auction.payment_mint.to_bytes()],
+        bump = auction.payment_escrow_bump,
+    )]
+    pub payment_escrow: Account<'info, TokenAccount>,
+
+    /// (PDA) The purchases of the buyer to refund
+    #[account(
+        mut,
+        seeds = [PURCHASE_RECEIPT_PREFIX, &auction.key().to_bytes(), &purchase_receipt.buyer.to_bytes()],
+        bump = purchase_receipt.bump,
+        constraint = !purchase_receipt.refunded @ AuctionError::AlreadyRefunded,
+    )]
+    pub purchase_receipt: Account<'info, PurchaseReceipt>,
+
+    /// A payment token account of the buyer to receive the refund
+    #[account(
+        mut,
+        constraint = refund_account.owner == purchase_receipt.buyer @ AuctionError::InvalidRefundAccount,
+        constraint = refund_account.mint == auction.payment_mint @ AuctionError::InvalidRefundAccount,
+    )]
+    pub refund_account: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct WithdrawProceeds<'info> {
+    /// The auction to withdraw the proceeds of
+    #[account(
+        mut,
+        has_one = authority @ AuctionError::InvalidAuctionAuthority,
+        has_one = payment_destination @ AuctionError::InvalidPaymentDestination,
+        constraint = auction.uniform_price @ AuctionError::AuctionNotUniformPrice,
+        constraint = auction.pending_refunds == 0 @ AuctionError::RefundsPending,
+    )]
+    pub auction: Account<'info, Auction>,
+
+    /// (PDA) The auction authority to sign the proceeds transfer
+    /// CHECK: PDA checked by its seeds, only signs for the token accounts of the auction
+    #[account(
+        seeds = [AUCTION_PREFIX, &auction.key().to_bytes()],
+        bump = auction.auction_authority_bump,
+    )]
+    pub auction_authority: AccountInfo<'info>,
+
+    /// (PDA) The escrow holding the payments
+    #[account(
+        mut,
+        seeds = [AUCTION_PREFIX, &auction.key().to_bytes(), &auction.payment_mint.to_bytes()],
+        bump = auction.payment_escrow_bump,
+    )]
+    pub payment_escrow: Account<'info, TokenAccount>,
+
+    /// The payment token account of the auction that receives the proceeds
+    #[account(mut)]
+    pub payment_destination: Account<'info, TokenAccount>,
+
+    /// The authority that controls the provided auction; receives the lamports of the escrow
+    /// CHECK: checked against the auction by `has_one`
+    #[account(
+        mut @ AuctionError::AuthorityNotMutable,
+        signer @ AuctionError::AuthorityNotSigner,
+    )]
+    pub authority: AccountInfo<'info>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct Claim<'info> {
+    /// The auction to claim from
+    #[account(mut)]
+    pub auction: Account<'info, Auction>,
+
+    /// (PDA) The auction authority to sign the sale token transfer
+    /// CHECK: PDA checked by its seeds, only signs for the token accounts of the auction
+    #[account(
+        seeds = [AUCTION_PREFIX, &auction.key().to_bytes()],
+        bump = auction.auction_authority_bump,
+    )]
+    pub auction_authority: AccountInfo<'info>,
+
+    /// (PDA) The auction pool holding the purchased sale tokens
+    #[account(
+        mut,
+        seeds = [AUCTION_PREFIX, &auction.key().to_bytes(), &auction.sale_mint.to_bytes()],
+        bump = auction.auction_pool_bump,
+    )]
+    pub auction_pool: Account<'info, TokenAccount>,
+
+    /// (PDA) The purchases of the buyer
+    #[account(
+        mut,
+        seeds = [PURCHASE_RECEIPT_PREFIX, &auction.key().to_bytes(), &buyer.key().to_bytes()],
+        bump = purchase_receipt.bump,
+    )]
+    pub purchase_receipt: Account<'info, PurchaseReceipt>,
+
+    /// The buyer who signs the claim
+    /// CHECK: checked by the seeds of the purchase receipt
+    #[account(signer)]
+    pub buyer: AccountInfo<'info>,
+
     /// The buyer's sale token account to receive the sale token
     #[account(
         mut,
@@ -589,10 +1500,22 @@ pub struct Purchase<'info> {
     pub token_program: Program<'info, Token>,
 }
 
+#[derive(Accounts)]
+pub struct QuotePurchase<'info> {
+    /// The auction to quote
+    pub auction: Account<'info, Auction>,
+
+    /// The sale token mint
+    #[account(
+        constraint = sale_mint.key() == auction.sale_mint,
+    )]
+    pub sale_mint: Account<'info, Mint>,
+}
+
 //
 // errors.rs
 //
-#[error]
+#[error_code]
 pub enum AuctionError {
     #[msg("The provided authority is not mutable")]
     AuthorityNotMutable, // 0x1770
@@ -647,6 +1570,66 @@ pub enum AuctionError {
 
     #[msg("Internal Error")]
     InternalError, // 0x1781
+
+    #[msg("The provided decay curve is not valid")]
+    InvalidDecayCurve, // 0x1782
+
+    #[msg("The provided price hold duration is not valid")]
+    InvalidPriceHoldDuration, // 0x1783
+
+    #[msg("The payment does not buy the provided minimum purchase amount")]
+    PurchaseAmountTooLow, // 0x1784
+
+    #[msg("The provided auction does not use a uniform clearing price")]
+    AuctionNotUniformPrice, // 0x1785
+
+    #[msg("The provided purchase receipt has already been refunded")]
+    AlreadyRefunded, // 0x1786
+
+    #[msg("The provided refund account is not a payment token account of the buyer")]
+    InvalidRefundAccount, // 0x1787
+
+    #[msg("Every buyer of the provided auction must be refunded first")]
+    RefundsPending, // 0x1788
+
+    #[msg("The proceeds of the provided auction must be withdrawn first")]
+    ProceedsNotWithdrawn, // 0x1789
+
+    #[msg("The provided auction must be in the Cancelled state")]
+    AuctionNotCancelled, // 0x178a
+
+    #[msg("The provided auction must be in either the Ended or Cancelled state")]
+    AuctionNotFinished, // 0x178b
+
+    #[msg("The provided auction must be in either the Pending or InProgress state")]
+    AuctionFinished, // 0x178c
+
+    #[msg("The provided purchase limits are not valid")]
+    InvalidPurchaseLimits, // 0x178d
+
+    #[msg("The purchase amount is below the minimum purchase of the auction")]
+    PurchaseBelowMinimum, // 0x178e
+
+    #[msg("The purchase amount exceeds the limit per transaction of the auction")]
+    PurchaseAboveTxLimit, // 0x178f
+
+    #[msg("The purchase amount exceeds the limit per buyer of the auction")]
+    PurchaseAboveBuyerLimit, // 0x1790
+
+    #[msg("The provided vesting schedule is not valid")]
+    InvalidVestingSchedule, // 0x1791
+
+    #[msg("The provided auction does not vest the purchased sale tokens")]
+    AuctionNotVesting, // 0x1792
+
+    #[msg("There are no vested sale tokens to claim")]
+    NothingToClaim, // 0x1793
+
+    #[msg("The provided account is not an auction in the legacy layout")]
+    InvalidAuctionLayout, // 0x1794
+
+    #[msg("Every purchased sale token of the provided auction must be claimed first")]
+    SaleTokensUnclaimed, // 0x1795
 }
 
 impl From<AuctionState> for AuctionError {
@@ -655,6 +1638,7 @@ impl From<AuctionState> for AuctionError {
             AuctionState::Pending => Self::AuctionNotPending,
             AuctionState::InProgress => Self::AuctionNotInProgress,
             AuctionState::Ended => Self::AuctionNotEnded,
+            AuctionState::Cancelled => Self::AuctionNotCancelled,
         }
     }
 }
@@ -676,15 +1660,14 @@ pub enum AuctionState {
     Pending,
     InProgress,
     Ended,
+    Cancelled,
 }
 
-// TODO: How should the current price react when a purchase has been made?
-//  - we could make it accept a function and used it to derive price given time passed from the last purchase?
-//  - but for now, *keep it simple*:
-//    - use `price_hold_duration` to have the price be held at the same value for a duration.
-//    - initialize `last_purchase_timestamp` as the starting time of the auction and save the timestamp of the last purchase
-//    - use it with `price_hold_duration` to hold the price for the specified duration
-// TODO: decide how to represent the early cancelation of auction
+// NOTE: How the current price reacts when a purchase has been made:
+//  - the price paid is saved as `last_purchase_price`, raised by `price_bump_bps` (up to `ceil_price`)
+//  - the raised price is held for `price_hold_duration` seconds after `last_purchase_timestamp`
+//  - then it resumes decaying along `decay_curve` from the raised price
+// NOTE: the authority or the guardian can cancel an auction early, see `cancel_auction`
 //
 // NOTE:
 //  - client figures out the current price by looking at on-chain state
@@ -695,10 +1678,61 @@ pub enum AuctionState {
 ///
 /// Invariants:
 ///  - auction_pool.owner = auction_authority.key()
-///  - `start_timestamp < end_timestamp`
-///  -
+///  - `start_timestamp < end_timestamp`, unless open-ended
+///
+/// New fields take their space from `reserved`, so that the account keeps its size; auctions in the
+/// `LegacyAuction` layout are rewritten by `migrate_auction`.
 #[account]
 pub struct Auction {
+    pub authority: Pubkey,
+    pub start_timestamp: i64,
+    pub end_timestamp: Option<i64>,
+    pub payment_mint: Pubkey,
+    pub payment_destination: Pubkey,
+    pub sale_mint: Pubkey,
+    pub auction_pool: Pubkey,
+    pub ceil_price: u64,
+    pub floor_price: u64,
+    pub price_hold_duration: i64,
+    pub auction_authority_bump: u8,
+    pub auction_pool_bump: u8,
+    pub last_purchase_timestamp: Option<i64>,
+    pub last_purchase_price: u64,
+    pub decay_curve: DecayCurve,
+    pub price_bump_bps: u64,
+    pub pricing_mode: PricingMode,
+    pub total_deposited: u64,
+    pub total_sold: u64,
+    // uniform clearing price, see `enable_uniform_price`
+    pub uniform_price: bool,
+    pub payment_escrow: Pubkey,
+    pub payment_escrow_bump: u8,
+    /// Price of the last fill, unrounded
+    pub clearing_price: Decimal,
+    pub pending_refunds: u64,
+    pub proceeds_withdrawn: bool,
+    pub ended: bool,
+    pub guardian: Option<Pubkey>,
+    pub cancelled: bool,
+    // sale statistics
+    /// Payment token received, before the refunds of a uniform clearing price
+    pub total_raised: u64,
+    pub purchase_count: u64,
+    // purchase limits, in sale token
+    pub max_per_buyer: Option<u64>,
+    pub max_per_tx: Option<u64>,
+    pub min_purchase: u64,
+    // vesting of the purchased sale tokens, see `update_vesting`
+    pub vesting: Option<VestingSchedule>,
+    pub total_claimed: u64,
+    /// `AUCTION_VERSION` of the layout
+    pub version: u8,
+    pub reserved: [u8; 64],
+}
+
+/// Layout of the auctions created before open-ended auctions, with a required `end_timestamp`
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct LegacyAuction {
     pub authority: Pubkey,
     pub start_timestamp: i64,
     pub end_timestamp: i64,
@@ -711,22 +1745,158 @@ pub struct Auction {
     pub price_hold_duration: i64,
     pub auction_authority_bump: u8,
     pub auction_pool_bump: u8,
-    // TODO: decide how the current price will be calculated with the information saved in auction account
     pub last_purchase_timestamp: Option<i64>,
     pub last_purchase_price: u64,
 }
 
-// TODO: define procedures to derive information from the on-chain state
+impl LegacyAuction {
+    /// Space allocated to a legacy auction account
+    pub const LEN: usize = 8 + size_of::<Self>();
+}
+
+impl From<LegacyAuction> for Auction {
+    fn from(legacy: LegacyAuction) -> Self {
+        Self {
+            authority: legacy.authority,
+            start_timestamp: legacy.start_timestamp,
+            end_timestamp: Some(legacy.end_timestamp),
+            payment_mint: legacy.payment_mint,
+            payment_destination: legacy.payment_destination,
+            sale_mint: legacy.sale_mint,
+            auction_pool: legacy.auction_pool,
+            ceil_price: legacy.ceil_price,
+            floor_price: legacy.floor_price,
+            price_hold_duration: legacy.price_hold_duration,
+            auction_authority_bump: legacy.auction_authority_bump,
+            auction_pool_bump: legacy.auction_pool_bump,
+            last_purchase_timestamp: legacy.last_purchase_timestamp,
+            last_purchase_price: legacy.last_purchase_price,
+            decay_curve: DecayCurve::Exponential {
+                half_life: LEGACY_HALF_LIFE,
+            },
+            price_bump_bps: 0,
+            pricing_mode: PricingMode::Time,
+            // deposits were not tracked, so a legacy auction is never sold out
+            total_deposited: 0,
+            total_sold: 0,
+            uniform_price: false,
+            payment_escrow: Pubkey::default(),
+            payment_escrow_bump: 0,
+            clearing_price: Decimal::zero(),
+            pending_refunds: 0,
+            proceeds_withdrawn: false,
+            ended: false,
+            guardian: None,
+            cancelled: false,
+            total_raised: 0,
+            purchase_count: 0,
+            max_per_buyer: None,
+            max_per_tx: None,
+            min_purchase: 0,
+            vesting: None,
+            total_claimed: 0,
+            version: AUCTION_VERSION,
+            reserved: [0; 64],
+        }
+    }
+}
+
+/// Purchases of a buyer in an auction
+#[account]
+pub struct PurchaseReceipt {
+    pub auction: Pubkey,
+    pub buyer: Pubkey,
+    pub purchased_amount: u64,
+    /// Payment token paid, before the refund of a uniform clearing price
+    pub paid_amount: u64,
+    pub last_purchase_timestamp: i64,
+    pub refunded: bool,
+    pub bump: u8,
+    /// Purchased sale token claimed with a vesting
+    pub claimed_amount: u64,
+}
+
+/// Release of the purchased sale tokens: nothing before the cliff, then linear until the end of the vesting
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq)]
+pub struct VestingSchedule {
+    pub start_timestamp: i64,
+    /// Seconds after `start_timestamp` before anything can be claimed
+    pub cliff_duration: i64,
+    /// Seconds after `start_timestamp` until everything is released
+    pub duration: i64,
+}
+
+impl VestingSchedule {
+    pub fn is_valid(&self) -> bool {
+        0 <= self.cliff_duration && self.cliff_duration <= self.duration && self.duration > 0
+    }
+
+    /// Part of a purchased amount released at a given time, rounded down
+    pub fn get_vested_amount(&self, purchased_amount: u64, current_timestamp: i64) -> u64 {
+        let elapsed = current_timestamp.saturating_sub(self.start_timestamp);
+        if elapsed < self.cliff_duration {
+            0
+        } else if elapsed >= self.duration {
+            purchased_amount
+        } else {
+            // elapsed < duration, so the result is below purchased_amount
+            (purchased_amount as u128 * elapsed as u128 / self.duration as u128) as u64
+        }
+    }
+}
+
+/// Shape of the price descent from `ceil_price` to `floor_price`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq)]
+pub enum DecayCurve {
+    /// Straight line from `ceil_price` at `start_timestamp` to `floor_price` at `end_timestamp`
+    Linear,
+    /// Price halves every `half_life` seconds until it reaches `floor_price`
+    Exponential { half_life: i64 },
+    /// Linear descent, lowered once every `step_duration` seconds
+    Stepwise { step_duration: i64 },
+}
+
+/// Inputs of the price descent
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq)]
+pub enum PricingMode {
+    /// The price decays with the time elapsed
+    Time,
+    /// The time elapsed is scaled by the fraction of the deposited sale tokens that remains unsold,
+    /// so a fast sell-through slows the descent
+    ///
+    /// The decay restarts from the price of each purchase, so every stretch between two purchases
+    /// decays at the rate of the supply remaining during it; a purchase never raises the price
+    /// beyond `price_bump_bps`.
+    TimeAndSupply,
+}
+
+impl DecayCurve {
+    pub fn is_valid(&self) -> bool {
+        match *self {
+            DecayCurve::Linear => true,
+            DecayCurve::Exponential { half_life } => half_life > 0,
+            DecayCurve::Stepwise { step_duration } => step_duration > 0,
+        }
+    }
+}
+
+// NOTE: procedures to derive information from the on-chain state
 // These are some of the questions that needs to be answered looking at the on-chain state
-//  - is the auction started? can I buy it yet or what? or is it ended alread?
-//  - what is the current price?
-//  - how much has been sold?
+//  - is the auction started? can I buy it yet or what? or is it ended alread? -> `get_current_auction_state`
+//  - what is the current price? -> `get_price_at`
+//  - how much has been sold? -> `total_sold`, `total_raised`, `purchase_count` and the `PurchaseReceipt` of each buyer
 impl Auction {
     pub fn get_current_auction_state(&self, current_timestamp: i64) -> AuctionState {
-        if current_timestamp < self.start_timestamp {
+        if self.cancelled {
+            AuctionState::Cancelled
+        } else if current_timestamp < self.start_timestamp {
             AuctionState::Pending
         } else if self.start_timestamp <= current_timestamp
-            && current_timestamp < self.end_timestamp
+            && self
+                .end_timestamp
+                .map_or(true, |end_timestamp| current_timestamp < end_timestamp)
+            && !self.ended
+            && !self.is_sold_out()
         {
             AuctionState::InProgress
         } else {
@@ -734,7 +1904,71 @@ impl Auction {
         }
     }
 
-    pub fn assert_auction_state(&self, expected_state: AuctionState) -> ProgramResult {
+    /// Whether the whole pool has been sold; fully subscribed with a uniform clearing price
+    ///
+    /// Never before a first deposit, so that an open-ended auction does not end while its pool is empty.
+    pub fn is_sold_out(&self) -> bool {
+        self.total_deposited > 0 && self.total_sold >= self.total_deposited
+    }
+
+    /// Purchased sale token still held in the auction pool for vesting
+    pub fn get_unclaimed_amount(&self) -> u64 {
+        match self.vesting {
+            Some(_) => self.total_sold.saturating_sub(self.total_claimed),
+            None => 0,
+        }
+    }
+
+    /// Sale token of the auction pool that can still be purchased
+    pub fn get_available_amount(&self, pool_amount: u64) -> u64 {
+        pool_amount.saturating_sub(self.get_unclaimed_amount())
+    }
+
+    /// Largest amount a buyer who already purchased `purchased_amount` can purchase in one transaction
+    pub fn get_purchase_allowance(&self, purchased_amount: u64) -> u64 {
+        let buyer_allowance = self.max_per_buyer.map_or(u64::MAX, |max_per_buyer| {
+            max_per_buyer.saturating_sub(purchased_amount)
+        });
+        buyer_allowance.min(self.max_per_tx.unwrap_or(u64::MAX))
+    }
+
+    /// Assert a purchase to respect the purchase limits, given the amount the buyer already purchased
+    pub fn assert_purchase_limits(
+        &self,
+        purchase_amount: u64,
+        purchased_amount: u64,
+    ) -> Result<()> {
+        if purchase_amount < self.min_purchase {
+            return Err(AuctionError::PurchaseBelowMinimum.into());
+        }
+        if self
+            .max_per_tx
+            .map_or(false, |max_per_tx| purchase_amount > max_per_tx)
+        {
+            return Err(AuctionError::PurchaseAboveTxLimit.into());
+        }
+        let total_purchased = purchased_amount
+            .checked_add(purchase_amount)
+            .ok_or(AuctionError::InternalError)?;
+        if self
+            .max_per_buyer
+            .map_or(false, |max_per_buyer| total_purchased > max_per_buyer)
+        {
+            return Err(AuctionError::PurchaseAboveBuyerLimit.into());
+        }
+        Ok(())
+    }
+
+    /// Token account the payments are sent to
+    pub fn get_payment_receiver(&self) -> Pubkey {
+        if self.uniform_price {
+            self.payment_escrow
+        } else {
+            self.payment_destination
+        }
+    }
+
+    pub fn assert_auction_state(&self, expected_state: AuctionState) -> Result<()> {
         let clock = Clock::get()?;
         if self.get_current_auction_state(clock.unix_timestamp) != expected_state {
             Err(AuctionError::from(expected_state).into())
@@ -744,50 +1978,269 @@ impl Auction {
     }
 
     /// Assert the auction to be not InProgress
-    pub fn assert_auction_state_not_in_progress(&self) -> ProgramResult {
+    pub fn assert_auction_state_not_in_progress(&self) -> Result<()> {
         self.assert_auction_state(AuctionState::Pending)
             .or(self.assert_auction_state(AuctionState::Ended))
+            .or(self.assert_auction_state(AuctionState::Cancelled))
             .map_err(|_| AuctionError::AuctionInProgress.into())
     }
 
-    fn _get_duration(&self) -> Option<i64> {
-        self.end_timestamp.checked_sub(self.start_timestamp)
+    /// Assert the auction to be Ended or Cancelled
+    pub fn assert_auction_state_finished(&self) -> Result<()> {
+        self.assert_auction_state(AuctionState::Ended)
+            .or(self.assert_auction_state(AuctionState::Cancelled))
+            .map_err(|_| AuctionError::AuctionNotFinished.into())
     }
 
-    /// Calculate the amount of payment token for a given amount of sale token at current time
-    pub fn get_payment_amount(&self, purchase_amount: u64, sale_decimals: u8) -> Result<u64> {
-        let base = PreciseNumber {
-            value: U256::from(1_000_050_000_000u128),
+    /// Assert the auction to be Pending or InProgress
+    pub fn assert_auction_state_not_finished(&self) -> Result<()> {
+        self.assert_auction_state(AuctionState::Pending)
+            .or(self.assert_auction_state(AuctionState::InProgress))
+            .map_err(|_| AuctionError::AuctionFinished.into())
+    }
+
+    /// Whether a given signer can cancel the auction: its authority or its guardian
+    pub fn can_cancel(&self, canceller: &Pubkey) -> bool {
+        *canceller == self.authority || self.guardian == Some(*canceller)
+    }
+
+    fn get_duration(&self) -> Option<i64> {
+        self.end_timestamp?.checked_sub(self.start_timestamp)
+    }
+
+    /// Price of a whole sale token at a given time
+    ///
+    /// Starts at `ceil_price` and decays along the decay curve; after a purchase the raised purchase
+    /// price is held for `price_hold_duration` seconds and decays from there. Never below `floor_price`.
+    pub fn get_price_at(&self, current_timestamp: i64) -> Option<Decimal> {
+        let (start_price, decay_start_timestamp) = match self.last_purchase_timestamp {
+            Some(last_purchase_timestamp) => (
+                self.get_held_price()?,
+                last_purchase_timestamp.checked_add(self.price_hold_duration)?,
+            ),
+            None => (Decimal::from_amount(self.ceil_price), self.start_timestamp),
         };
 
-        // calculate the time since start of auction
-        let t = (Clock::get()?.unix_timestamp - self.start_timestamp) as u128;
-        // TODO: t -> f(t, remaining precentage of sale token)
-        let decay_factor = base.checked_pow(t);
+        // calculate the time since the price started decaying
+        let elapsed = current_timestamp.checked_sub(decay_start_timestamp)?.max(0);
+        // t -> f(t, remaining precentage of sale token)
+        let elapsed = match self.pricing_mode {
+            PricingMode::Time => elapsed,
+            PricingMode::TimeAndSupply => self.scale_by_remaining_supply(elapsed)?,
+        };
+        let floor_price = Decimal::from_amount(self.floor_price);
+
+        let current_price = match self.decay_curve {
+            DecayCurve::Linear => self.get_linear_price(start_price, elapsed)?,
+            DecayCurve::Exponential { half_life } => {
+                // 2^(t / half_life) = 2^halvings * e^(fraction * ln 2)
+                let halvings = (elapsed / half_life) as u64;
+                let fraction = Decimal::from_ratio((elapsed % half_life) as u64, half_life as u64)?;
+                let decay_factor =
+                    Decimal::from_amount(2)
+                        .checked_pow(halvings)
+                        .and_then(|factor| {
+                            factor.checked_mul(
+                                fraction
+                                    .checked_mul(Decimal::from_raw(LN_2))?
+                                    .checked_exp()?,
+                            )
+                        });
+                // a decay factor too large to represent has reached the floor
+                decay_factor
+                    .and_then(|decay_factor| start_price.checked_div(decay_factor))
+                    .unwrap_or(floor_price)
+            }
+            DecayCurve::Stepwise { step_duration } => {
+                self.get_linear_price(start_price, elapsed - elapsed % step_duration)?
+            }
+        };
 
         // max(floor_price, current_price)
-        let floor_price = PreciseNumber::new(self.floor_price as u128);
-        let current_price = PreciseNumber::new(self.ceil_price as u128)
-            .and_then(|v| v.checked_div(&decay_factor?))
-            .and_then(|v| {
-                let floor_price = floor_price?;
-                Some(if v.greater_than(&floor_price) {
-                    v
-                } else {
-                    floor_price
-                })
-            });
-
-        // calculate the payment amount: price * sale_amount
-        let sale_decimals = 10u128
-            .checked_pow(sale_decimals.into())
-            .and_then(PreciseNumber::new);
-
-        PreciseNumber::new(purchase_amount as u128)
-            .and_then(|v| v.checked_mul(&current_price?))
-            .and_then(|v| v.checked_div(&sale_decimals?))
-            .and_then(|v| v.ceiling())
-            .and_then(|v| u64::try_from(v.to_imprecise()?).ok())
+        Some(current_price.max(floor_price))
+    }
+
+    /// `duration * remaining / total_deposited`, rounded down; unchanged before any deposit
+    ///
+    /// Only applied to the time since the last purchase, the supply being constant over it: deposits
+    /// and withdrawals are not allowed in progress.
+    fn scale_by_remaining_supply(&self, duration: i64) -> Option<i64> {
+        if self.total_deposited == 0 {
+            return Some(duration);
+        }
+        let remaining = self.total_deposited.saturating_sub(self.total_sold);
+        let scaled = (duration as u128)
+            .checked_mul(remaining as u128)?
+            .checked_div(self.total_deposited as u128)?;
+        i64::try_from(scaled).ok()
+    }
+
+    /// Last purchase price raised by `price_bump_bps`, up to `ceil_price`
+    fn get_held_price(&self) -> Option<Decimal> {
+        let bump = Decimal::one().checked_add(Decimal::from_bps(self.price_bump_bps)?)?;
+        let held_price = Decimal::from_amount(self.last_purchase_price).checked_mul(bump)?;
+        Some(held_price.min(Decimal::from_amount(self.ceil_price)))
+    }
+
+    /// Descends from `start_price` at the slope of the line from `ceil_price` to `floor_price` over the auction
+    fn get_linear_price(&self, start_price: Decimal, elapsed: i64) -> Option<Decimal> {
+        let duration = self.get_duration()?;
+        let progress = Decimal::from_ratio(elapsed as u64, duration as u64)?;
+        let price_range = Decimal::from_amount(self.ceil_price.checked_sub(self.floor_price)?);
+        // a descent past zero has reached the floor
+        Some(
+            start_price
+                .checked_sub(price_range.checked_mul(progress)?)
+                .unwrap_or_else(Decimal::zero),
+        )
+    }
+
+    /// Calculate the amount of payment token for a given amount of sale token at current time
+    pub fn get_payment_amount(&self, purchase_amount: u64, sale_decimals: u8) -> Result<u64> {
+        self.get_payment_amount_at(purchase_amount, sale_decimals, Clock::get()?.unix_timestamp)
+    }
+
+    /// Calculate the amount of payment token for a given amount of sale token at a given time
+    pub fn get_payment_amount_at(
+        &self,
+        purchase_amount: u64,
+        sale_decimals: u8,
+        current_timestamp: i64,
+    ) -> Result<u64> {
+        let current_price = self
+            .get_price_at(current_timestamp)
+            .ok_or(AuctionError::InternalError)?;
+        Self::get_payment_amount_at_price(current_price, purchase_amount, sale_decimals)
+    }
+
+    /// Calculate the amount of sale token a given amount of payment token buys at a given price, rounded down
+    ///
+    /// The price per base unit of sale token is rounded up, against the buyer.
+    pub fn get_purchase_amount_at_price(
+        current_price: Decimal,
+        payment_amount: u64,
+        sale_decimals: u8,
+    ) -> Result<u64> {
+        current_price
+            .to_base_units(sale_decimals, 0, Rounding::Up)
+            .and_then(|price| price.div_amount(payment_amount, Rounding::Down))
+            .ok_or(AuctionError::InternalError.into())
+    }
+
+    /// Calculate the amount of payment token for a given amount of sale token at a given price, rounded up
+    ///
+    /// The price per base unit of sale token is rounded up, against the buyer.
+    pub fn get_payment_amount_at_price(
+        current_price: Decimal,
+        purchase_amount: u64,
+        sale_decimals: u8,
+    ) -> Result<u64> {
+        // calculate the payment amount: price * sale_amount, the price being per whole sale token
+        current_price
+            .to_base_units(sale_decimals, 0, Rounding::Up)
+            .and_then(|price| price.mul_amount(purchase_amount, Rounding::Up))
             .ok_or(AuctionError::InternalError.into())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 1000 sale tokens from 100 down to 10 over 1000 seconds, decaying with the remaining supply
+    fn supply_auction() -> Auction {
+        let mut auction = Auction::from(LegacyAuction {
+            authority: Pubkey::default(),
+            start_timestamp: 0,
+            end_timestamp: 1000,
+            payment_mint: Pubkey::default(),
+            payment_destination: Pubkey::default(),
+            sale_mint: Pubkey::default(),
+            auction_pool: Pubkey::default(),
+            ceil_price: 100,
+            floor_price: 10,
+            price_hold_duration: 0,
+            auction_authority_bump: 0,
+            auction_pool_bump: 0,
+            last_purchase_timestamp: None,
+            last_purchase_price: 0,
+        });
+        auction.decay_curve = DecayCurve::Linear;
+        auction.pricing_mode = PricingMode::TimeAndSupply;
+        auction.total_deposited = 1000;
+        auction
+    }
+
+    /// Records a purchase of `amount` at `timestamp` as `settle_purchase` does
+    fn record_purchase(auction: &mut Auction, amount: u64, timestamp: i64) {
+        let price = auction.get_price_at(timestamp).unwrap();
+        auction.last_purchase_timestamp = Some(timestamp);
+        auction.last_purchase_price = price.to_u64(Rounding::Up).unwrap();
+        auction.total_sold += amount;
+    }
+
+    #[test]
+    fn base_unit_price_rounds_against_the_buyer() {
+        // a third of a payment token per whole sale token of 18 decimals is below 1e-18 per base unit
+        let price = Decimal::from_ratio(1, 3).unwrap();
+        assert_eq!(
+            Auction::get_payment_amount_at_price(price, 1, 18).ok(),
+            Some(1)
+        );
+        // rounded up to 1e-18, rather than down to a zero price
+        assert_eq!(
+            Auction::get_purchase_amount_at_price(price, 1, 18).ok(),
+            Some(1_000_000_000_000_000_000)
+        );
+        assert_eq!(
+            Auction::get_payment_amount_at_price(price, 3, 0).ok(),
+            Some(1)
+        );
+    }
+
+    #[test]
+    fn supply_pricing_does_not_raise_the_price_on_purchase() {
+        let mut auction = supply_auction();
+        assert_eq!(auction.get_price_at(500), Some(Decimal::from_amount(55)));
+
+        record_purchase(&mut auction, 500, 500);
+        assert_eq!(auction.get_price_at(500), Some(Decimal::from_amount(55)));
+        // half the supply remains, so the next 200 seconds decay as 100 seconds would
+        assert_eq!(auction.get_price_at(700), Some(Decimal::from_amount(46)));
+
+        record_purchase(&mut auction, 250, 700);
+        let mut previous = auction.get_price_at(700).unwrap();
+        for timestamp in (710..=2000).step_by(10) {
+            let price = auction.get_price_at(timestamp).unwrap();
+            assert!(price <= previous, "price rose at {}", timestamp);
+            previous = price;
+        }
+        // a quarter of the supply remains, so the 1300 seconds since decay as 325 would
+        assert_eq!(previous, Decimal::from_raw(16_750_000_000_000_000_000));
+    }
+
+    #[test]
+    fn supply_pricing_slows_the_descent() {
+        let mut time_only = supply_auction();
+        time_only.pricing_mode = PricingMode::Time;
+        let mut auction = supply_auction();
+
+        record_purchase(&mut time_only, 500, 500);
+        record_purchase(&mut auction, 500, 500);
+        assert_eq!(time_only.get_price_at(700), Some(Decimal::from_amount(37)));
+        assert_eq!(auction.get_price_at(700), Some(Decimal::from_amount(46)));
+    }
+
+    #[test]
+    fn supply_pricing_raises_the_price_by_the_bump_only() {
+        let mut auction = supply_auction();
+        auction.price_bump_bps = 100;
+        auction.price_hold_duration = 60;
+
+        record_purchase(&mut auction, 500, 500);
+        let bumped = Decimal::from_raw(55_550_000_000_000_000_000);
+        assert_eq!(auction.get_price_at(500), Some(bumped));
+        assert_eq!(auction.get_price_at(560), Some(bumped));
+        assert!(auction.get_price_at(600).unwrap() < bumped);
+    }
+}
@@ -0,0 +1,828 @@
+mod program_test;
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::{entrypoint::ProgramResult, instruction::Instruction};
+use anchor_lang::Discriminator;
+use bonding_client::auction::{
+    self as client, Auction, DecayCurve, PricingMode, PurchaseReceipt, VestingSchedule,
+};
+use bonding_math::decimal::Decimal;
+use descending_auction_program::{
+    AnchorLen, AuctionError, LegacyAuction, AUCTION_VERSION, LEGACY_HALF_LIFE,
+};
+use program_test::{ProgramTest, TestAccount};
+
+const START: i64 = 100;
+const END: i64 = 1100;
+const DEPOSIT: u64 = 1000;
+
+fn auction_error(error: AuctionError) -> ProgramError {
+    anchor_lang::error::Error::from(error).into()
+}
+
+/// Settings of the auction of a `Fixture`: 100 down to 10 payment base units per sale token
+struct Schedule {
+    end_timestamp: Option<i64>,
+    decay_curve: DecayCurve,
+    pricing_mode: PricingMode,
+    price_hold_duration: i64,
+    price_bump_bps: u64,
+}
+
+impl Default for Schedule {
+    fn default() -> Self {
+        Self {
+            end_timestamp: Some(END),
+            decay_curve: DecayCurve::Linear,
+            pricing_mode: PricingMode::Time,
+            price_hold_duration: 0,
+            price_bump_bps: 0,
+        }
+    }
+}
+
+struct Buyer {
+    wallet: Pubkey,
+    payment_account: Pubkey,
+    sale_account: Pubkey,
+}
+
+/// A pending auction of a sale token without decimals
+struct Fixture {
+    test: ProgramTest,
+    authority: Pubkey,
+    auction: Pubkey,
+    sale_mint: Pubkey,
+    payment_mint: Pubkey,
+    payment_destination: Pubkey,
+    sale_account: Pubkey,
+}
+
+impl Fixture {
+    fn new(schedule: Schedule) -> Self {
+        let mut test = ProgramTest::new();
+        let authority = test.add_wallet();
+        let sale_mint = test.create_mint(0);
+        let payment_mint = test.create_mint(6);
+        let payment_destination = test.create_token_account(&payment_mint, &authority, 0);
+        let sale_account = test.create_token_account(&sale_mint, &authority, DEPOSIT);
+        let auction = Pubkey::new_unique();
+        let instruction = client::initialize_auction(
+            &auction,
+            &authority,
+            &payment_mint,
+            &payment_destination,
+            &sale_mint,
+            START,
+            schedule.end_timestamp,
+            100,
+            10,
+            schedule.decay_curve,
+            schedule.pricing_mode,
+            schedule.price_hold_duration,
+            schedule.price_bump_bps,
+        );
+        test.process(&[instruction], &[&authority, &auction])
+            .unwrap();
+        Self {
+            test,
+            authority,
+            auction,
+            sale_mint,
+            payment_mint,
+            payment_destination,
+            sale_account,
+        }
+    }
+
+    fn state(&self) -> Auction {
+        self.test.get(&self.auction)
+    }
+
+    fn receipt(&self, buyer: &Buyer) -> PurchaseReceipt {
+        self.test
+            .get(&client::purchase_receipt_address(&self.auction, &buyer.wallet).0)
+    }
+
+    fn process_as_authority(&mut self, instruction: Instruction) -> ProgramResult {
+        let authority = self.authority;
+        self.test.process(&[instruction], &[&authority])
+    }
+
+    fn deposit(&mut self, amount: u64) {
+        let instruction = client::deposit_to_auction_pool(
+            &self.auction,
+            &self.authority,
+            &self.sale_mint,
+            &self.sale_account,
+            amount,
+        );
+        self.process_as_authority(instruction).unwrap();
+    }
+
+    fn add_buyer(&mut self) -> Buyer {
+        let wallet = self.test.add_wallet();
+        Buyer {
+            wallet,
+            payment_account: self
+                .test
+                .create_token_account(&self.payment_mint, &wallet, 1_000_000),
+            sale_account: self.test.create_token_account(&self.sale_mint, &wallet, 0),
+        }
+    }
+
+    fn purchase(&mut self, buyer: &Buyer, purchase_amount: u64) -> ProgramResult {
+        let instruction = client::purchase(
+            &self.auction,
+            &self.state(),
+            &buyer.wallet,
+            &buyer.payment_account,
+            &buyer.sale_account,
+            purchase_amount,
+            u64::MAX,
+            0,
+        );
+        self.test.process(&[instruction], &[&buyer.wallet])
+    }
+
+    /// Payment token spent by a buyer
+    fn paid(&self, buyer: &Buyer) -> u64 {
+        1_000_000 - self.test.token_balance(&buyer.payment_account)
+    }
+
+    fn withdraw_unsold(&mut self) -> ProgramResult {
+        let instruction = client::withdraw_unsold(
+            &self.auction,
+            &self.authority,
+            &self.sale_mint,
+            &self.sale_account,
+        );
+        self.process_as_authority(instruction)
+    }
+
+    fn close(&mut self) -> ProgramResult {
+        let instruction = client::close_auction(
+            &self.auction,
+            &self.authority,
+            &self.sale_mint,
+            &self.sale_account,
+        );
+        self.process_as_authority(instruction)
+    }
+}
+
+#[test]
+fn initialize_auction() {
+    let mut fixture = Fixture::new(Schedule::default());
+    fixture.deposit(DEPOSIT);
+
+    let auction = fixture.state();
+    assert_eq!(auction.authority, fixture.authority);
+    assert_eq!(auction.start_timestamp, START);
+    assert_eq!(auction.end_timestamp, Some(END));
+    assert_eq!(auction.decay_curve, DecayCurve::Linear);
+    assert_eq!(auction.version, AUCTION_VERSION);
+    assert_eq!(auction.total_deposited, DEPOSIT);
+    assert_eq!(fixture.test.token_balance(&auction.auction_pool), DEPOSIT);
+}
+
+#[test]
+fn initialize_auction_rejects_invalid_settings() {
+    let mut fixture = Fixture::new(Schedule::default());
+    let authority = fixture.authority;
+    let (payment_mint, payment_destination, sale_mint) = (
+        fixture.payment_mint,
+        fixture.payment_destination,
+        fixture.sale_mint,
+    );
+    let initialize = |auction: &Pubkey, end_timestamp, decay_curve, floor_price| {
+        client::initialize_auction(
+            auction,
+            &authority,
+            &payment_mint,
+            &payment_destination,
+            &sale_mint,
+            START,
+            end_timestamp,
+            100,
+            floor_price,
+            decay_curve,
+            PricingMode::Time,
+            0,
+            0,
+        )
+    };
+
+    let auction = Pubkey::new_unique();
+    let instruction = initialize(&auction, Some(END), DecayCurve::Linear, 100);
+    assert_eq!(
+        fixture
+            .test
+            .process(&[instruction], &[&authority, &auction]),
+        Err(auction_error(AuctionError::InvalidAuctionPrice))
+    );
+    let instruction = initialize(&auction, None, DecayCurve::Linear, 10);
+    assert_eq!(
+        fixture
+            .test
+            .process(&[instruction], &[&authority, &auction]),
+        Err(auction_error(AuctionError::InvalidDecayCurve))
+    );
+    let instruction = initialize(&auction, None, DecayCurve::Exponential { half_life: 0 }, 10);
+    assert_eq!(
+        fixture
+            .test
+            .process(&[instruction], &[&authority, &auction]),
+        Err(auction_error(AuctionError::InvalidDecayCurve))
+    );
+    let instruction = initialize(
+        &auction,
+        None,
+        DecayCurve::Exponential { half_life: 100 },
+        10,
+    );
+    fixture
+        .test
+        .process(&[instruction], &[&authority, &auction])
+        .unwrap();
+}
+
+#[test]
+fn update_settings_while_pending() {
+    let mut fixture = Fixture::new(Schedule::default());
+    let (auction, authority) = (fixture.auction, fixture.authority);
+    let guardian = Pubkey::new_unique();
+    let vesting = VestingSchedule {
+        start_timestamp: 1200,
+        cliff_duration: 0,
+        duration: 100,
+    };
+
+    let instructions = [
+        client::update_start_time(&auction, &authority, 200),
+        client::update_end_time(&auction, &authority, 1200),
+        client::update_ceil_price(&auction, &authority, 200),
+        client::update_floor_price(&auction, &authority, 20),
+        client::update_purchase_limits(&auction, &authority, Some(50), Some(10), 2),
+        client::update_vesting(&auction, &authority, Some(vesting)),
+        client::update_guardian(&auction, &authority, Some(guardian)),
+    ];
+    fixture.test.process(&instructions, &[&authority]).unwrap();
+    let state = fixture.state();
+    assert_eq!(state.start_timestamp, 200);
+    assert_eq!(state.end_timestamp, Some(1200));
+    assert_eq!((state.ceil_price, state.floor_price), (200, 20));
+    assert_eq!(
+        (state.max_per_buyer, state.max_per_tx, state.min_purchase),
+        (Some(50), Some(10), 2)
+    );
+    assert_eq!(state.vesting, Some(vesting));
+    assert_eq!(state.guardian, Some(guardian));
+
+    assert_eq!(
+        fixture.process_as_authority(client::update_floor_price(&auction, &authority, 200)),
+        Err(auction_error(AuctionError::InvalidAuctionPrice))
+    );
+    assert_eq!(
+        fixture.process_as_authority(client::update_end_time(&auction, &authority, 200)),
+        Err(auction_error(AuctionError::InvalidAuctionTimestamps))
+    );
+    let stranger = fixture.test.add_wallet();
+    assert_eq!(
+        fixture.test.process(
+            &[client::update_ceil_price(&auction, &stranger, 300)],
+            &[&stranger]
+        ),
+        Err(auction_error(AuctionError::InvalidAuctionAuthority))
+    );
+
+    fixture.test.set_timestamp(300);
+    assert_eq!(
+        fixture.process_as_authority(client::update_ceil_price(&auction, &authority, 300)),
+        Err(auction_error(AuctionError::AuctionNotPending))
+    );
+}
+
+#[test]
+fn purchase_charges_the_current_price() {
+    let mut fixture = Fixture::new(Schedule::default());
+    fixture.deposit(DEPOSIT);
+    let buyer = fixture.add_buyer();
+
+    assert_eq!(
+        fixture.purchase(&buyer, 10),
+        Err(auction_error(AuctionError::AuctionNotInProgress))
+    );
+
+    // half way down from 100 to 10
+    fixture.test.set_timestamp(600);
+    fixture.purchase(&buyer, 10).unwrap();
+    assert_eq!(fixture.paid(&buyer), 550);
+    assert_eq!(fixture.test.token_balance(&buyer.sale_account), 10);
+    assert_eq!(
+        fixture.test.token_balance(&fixture.payment_destination),
+        550
+    );
+
+    let auction = fixture.state();
+    assert_eq!(auction.total_sold, 10);
+    assert_eq!(auction.total_raised, 550);
+    assert_eq!(auction.purchase_count, 1);
+    assert_eq!(auction.last_purchase_timestamp, Some(600));
+    assert_eq!(auction.last_purchase_price, 55);
+    let receipt = fixture.receipt(&buyer);
+    assert_eq!(receipt.buyer, buyer.wallet);
+    assert_eq!((receipt.purchased_amount, receipt.paid_amount), (10, 550));
+
+    assert_eq!(
+        fixture.purchase(&buyer, 0),
+        Err(auction_error(AuctionError::PurchaseAmountTooLow))
+    );
+    assert_eq!(
+        fixture.purchase(&buyer, DEPOSIT),
+        Err(auction_error(AuctionError::AuctionPoolBalanceTooLow))
+    );
+}
+
+#[test]
+fn purchase_rejects_a_price_out_of_slippage() {
+    let mut fixture = Fixture::new(Schedule::default());
+    fixture.deposit(DEPOSIT);
+    let buyer = fixture.add_buyer();
+    fixture.test.set_timestamp(600);
+
+    // 550 is more than 500 and 5%
+    let purchase = |fixture: &Fixture, slippage_tolerance| {
+        client::purchase(
+            &fixture.auction,
+            &fixture.state(),
+            &buyer.wallet,
+            &buyer.payment_account,
+            &buyer.sale_account,
+            10,
+            500,
+            slippage_tolerance,
+        )
+    };
+    let instruction = purchase(&fixture, 50);
+    assert_eq!(
+        fixture.test.process(&[instruction], &[&buyer.wallet]),
+        Err(auction_error(AuctionError::PurchasePriceOutOfSlippage))
+    );
+    let instruction = purchase(&fixture, 100);
+    fixture
+        .test
+        .process(&[instruction], &[&buyer.wallet])
+        .unwrap();
+    assert_eq!(fixture.paid(&buyer), 550);
+}
+
+#[test]
+fn purchase_holds_and_bumps_the_price() {
+    let mut fixture = Fixture::new(Schedule {
+        price_hold_duration: 60,
+        price_bump_bps: 1000,
+        ..Schedule::default()
+    });
+    fixture.deposit(DEPOSIT);
+    let buyer = fixture.add_buyer();
+
+    fixture.test.set_timestamp(600);
+    fixture.purchase(&buyer, 10).unwrap();
+    // held at 55 and 10%
+    fixture.test.set_timestamp(660);
+    fixture.purchase(&buyer, 10).unwrap();
+    assert_eq!(fixture.paid(&buyer), 550 + 605);
+    // held at 61 (60.5 rounded up) and 10% for 60 seconds, then decaying by 0.09 a second
+    fixture.test.set_timestamp(760);
+    fixture.purchase(&buyer, 10).unwrap();
+    assert_eq!(fixture.paid(&buyer), 550 + 605 + 635);
+}
+
+#[test]
+fn purchase_with_supply_pricing_slows_the_descent() {
+    let mut fixture = Fixture::new(Schedule {
+        pricing_mode: PricingMode::TimeAndSupply,
+        ..Schedule::default()
+    });
+    fixture.deposit(DEPOSIT);
+    let buyer = fixture.add_buyer();
+
+    fixture.test.set_timestamp(600);
+    fixture.purchase(&buyer, 500).unwrap();
+    assert_eq!(fixture.paid(&buyer), 500 * 55);
+    // half the supply remains, so 200 seconds decay as 100
+    fixture.test.set_timestamp(800);
+    fixture.purchase(&buyer, 10).unwrap();
+    assert_eq!(fixture.paid(&buyer), 500 * 55 + 460);
+}
+
+#[test]
+fn purchase_exact_payment_buys_what_the_payment_covers() {
+    let mut fixture = Fixture::new(Schedule::default());
+    fixture.deposit(DEPOSIT);
+    let buyer = fixture.add_buyer();
+    fixture.test.set_timestamp(600);
+
+    let purchase = |fixture: &Fixture, min_purchase_amount| {
+        client::purchase_exact_payment(
+            &fixture.auction,
+            &fixture.state(),
+            &buyer.wallet,
+            &buyer.payment_account,
+            &buyer.sale_account,
+            1000,
+            min_purchase_amount,
+        )
+    };
+    let instruction = purchase(&fixture, 19);
+    assert_eq!(
+        fixture.test.process(&[instruction], &[&buyer.wallet]),
+        Err(auction_error(AuctionError::PurchaseAmountTooLow))
+    );
+    let instruction = purchase(&fixture, 18);
+    fixture
+        .test
+        .process(&[instruction], &[&buyer.wallet])
+        .unwrap();
+    assert_eq!(fixture.test.token_balance(&buyer.sale_account), 18);
+    assert_eq!(fixture.paid(&buyer), 18 * 55);
+}
+
+#[test]
+fn purchase_respects_the_purchase_limits() {
+    let mut fixture = Fixture::new(Schedule::default());
+    let (auction, authority) = (fixture.auction, fixture.authority);
+    fixture
+        .process_as_authority(client::update_purchase_limits(
+            &auction,
+            &authority,
+            Some(15),
+            Some(10),
+            2,
+        ))
+        .unwrap();
+    fixture.deposit(DEPOSIT);
+    let buyer = fixture.add_buyer();
+    fixture.test.set_timestamp(600);
+
+    assert_eq!(
+        fixture.purchase(&buyer, 1),
+        Err(auction_error(AuctionError::PurchaseBelowMinimum))
+    );
+    assert_eq!(
+        fixture.purchase(&buyer, 11),
+        Err(auction_error(AuctionError::PurchaseAboveTxLimit))
+    );
+    fixture.purchase(&buyer, 10).unwrap();
+    assert_eq!(
+        fixture.purchase(&buyer, 6),
+        Err(auction_error(AuctionError::PurchaseAboveBuyerLimit))
+    );
+    fixture.purchase(&buyer, 5).unwrap();
+    assert_eq!(fixture.receipt(&buyer).purchased_amount, 15);
+}
+
+#[test]
+fn open_ended_auction_ends_when_sold_out() {
+    let mut fixture = Fixture::new(Schedule {
+        end_timestamp: None,
+        decay_curve: DecayCurve::Exponential { half_life: 100 },
+        ..Schedule::default()
+    });
+    fixture.deposit(100);
+    let buyer = fixture.add_buyer();
+
+    // one half-life after the start
+    fixture.test.set_timestamp(START + 100);
+    fixture.purchase(&buyer, 60).unwrap();
+    assert_eq!(fixture.paid(&buyer), 60 * 50);
+    fixture.purchase(&buyer, 40).unwrap();
+    assert_eq!(
+        fixture.purchase(&buyer, 1),
+        Err(auction_error(AuctionError::AuctionNotInProgress))
+    );
+    fixture.close().unwrap();
+}
+
+#[test]
+fn end_auction_stops_purchases() {
+    let mut fixture = Fixture::new(Schedule::default());
+    fixture.deposit(DEPOSIT);
+    let buyer = fixture.add_buyer();
+    let (auction, authority) = (fixture.auction, fixture.authority);
+
+    assert_eq!(
+        fixture.process_as_authority(client::end_auction(&auction, &authority)),
+        Err(auction_error(AuctionError::AuctionNotInProgress))
+    );
+    fixture.test.set_timestamp(600);
+    fixture.purchase(&buyer, 10).unwrap();
+    assert_eq!(
+        fixture.withdraw_unsold(),
+        Err(auction_error(AuctionError::AuctionInProgress))
+    );
+    fixture
+        .process_as_authority(client::end_auction(&auction, &authority))
+        .unwrap();
+    assert_eq!(
+        fixture.purchase(&buyer, 10),
+        Err(auction_error(AuctionError::AuctionNotInProgress))
+    );
+
+    fixture.withdraw_unsold().unwrap();
+    assert_eq!(fixture.test.token_balance(&fixture.sale_account), 990);
+    // withdrawn tokens no longer count as deposited
+    let state = fixture.state();
+    assert_eq!((state.total_deposited, state.total_sold), (10, 10));
+}
+
+#[test]
+fn cancel_auction_by_the_guardian() {
+    let mut fixture = Fixture::new(Schedule::default());
+    let guardian = fixture.test.add_wallet();
+    let stranger = fixture.test.add_wallet();
+    let (auction, authority) = (fixture.auction, fixture.authority);
+    fixture
+        .process_as_authority(client::update_guardian(
+            &auction,
+            &authority,
+            Some(guardian),
+        ))
+        .unwrap();
+    fixture.deposit(DEPOSIT);
+    let buyer = fixture.add_buyer();
+    fixture.test.set_timestamp(600);
+
+    assert_eq!(
+        fixture
+            .test
+            .process(&[client::cancel_auction(&auction, &stranger)], &[&stranger]),
+        Err(auction_error(AuctionError::InvalidAuctionAuthority))
+    );
+    fixture
+        .test
+        .process(&[client::cancel_auction(&auction, &guardian)], &[&guardian])
+        .unwrap();
+    assert!(fixture.state().cancelled);
+    assert_eq!(
+        fixture.purchase(&buyer, 10),
+        Err(auction_error(AuctionError::AuctionNotInProgress))
+    );
+    assert_eq!(
+        fixture
+            .test
+            .process(&[client::cancel_auction(&auction, &guardian)], &[&guardian]),
+        Err(auction_error(AuctionError::AuctionFinished))
+    );
+
+    // the pool and the rent of both accounts go back to the authority
+    let pool = fixture.state().auction_pool;
+    let lamports = fixture.test.lamports(&authority)
+        + fixture.test.lamports(&auction)
+        + fixture.test.lamports(&pool);
+    fixture.close().unwrap();
+    assert_eq!(fixture.test.token_balance(&fixture.sale_account), DEPOSIT);
+    assert!(fixture.test.account(&auction).is_none());
+    assert!(fixture.test.account(&pool).is_none());
+    assert_eq!(fixture.test.lamports(&authority), lamports);
+}
+
+#[test]
+fn uniform_price_refunds_to_the_clearing_price() {
+    let mut fixture = Fixture::new(Schedule::default());
+    let (auction, authority) = (fixture.auction, fixture.authority);
+    let payment_mint = fixture.payment_mint;
+    fixture
+        .process_as_authority(client::enable_uniform_price(
+            &auction,
+            &authority,
+            &payment_mint,
+        ))
+        .unwrap();
+    fixture.deposit(DEPOSIT);
+    let early = fixture.add_buyer();
+    let late = fixture.add_buyer();
+    let escrow = fixture.state().payment_escrow;
+
+    fixture.test.set_timestamp(600);
+    assert_eq!(
+        fixture.process_as_authority(client::withdraw_proceeds(&auction, &fixture.state())),
+        Err(auction_error(AuctionError::AuctionNotFinished))
+    );
+    fixture.purchase(&early, 10).unwrap();
+    fixture.test.set_timestamp(800);
+    fixture.purchase(&late, 10).unwrap();
+    assert_eq!(fixture.test.token_balance(&escrow), 550 + 370);
+    assert_eq!(fixture.state().clearing_price, Decimal::from_amount(37));
+
+    fixture.test.set_timestamp(END);
+    assert_eq!(
+        fixture.process_as_authority(client::withdraw_proceeds(&auction, &fixture.state())),
+        Err(auction_error(AuctionError::RefundsPending))
+    );
+    for buyer in [&early, &late].iter() {
+        let instruction = client::claim_refund(
+            &auction,
+            &fixture.state(),
+            &buyer.wallet,
+            &buyer.payment_account,
+        );
+        fixture.process_as_authority(instruction).unwrap();
+        assert_eq!(fixture.paid(buyer), 370);
+    }
+    let instruction = client::claim_refund(
+        &auction,
+        &fixture.state(),
+        &early.wallet,
+        &early.payment_account,
+    );
+    assert_eq!(
+        fixture.process_as_authority(instruction),
+        Err(auction_error(AuctionError::AlreadyRefunded))
+    );
+
+    // the authority has to sign
+    let mut instruction = client::withdraw_proceeds(&auction, &fixture.state());
+    instruction
+        .accounts
+        .iter_mut()
+        .filter(|meta| meta.pubkey == authority)
+        .for_each(|meta| meta.is_signer = false);
+    assert_eq!(
+        fixture.test.process(&[instruction], &[]),
+        Err(auction_error(AuctionError::AuthorityNotSigner))
+    );
+    fixture
+        .process_as_authority(client::withdraw_proceeds(&auction, &fixture.state()))
+        .unwrap();
+    assert_eq!(
+        fixture.test.token_balance(&fixture.payment_destination),
+        740
+    );
+    assert!(fixture.test.account(&escrow).is_none());
+    assert!(fixture.state().proceeds_withdrawn);
+    fixture.close().unwrap();
+}
+
+#[test]
+fn vesting_releases_purchases_through_claim() {
+    let mut fixture = Fixture::new(Schedule::default());
+    let (auction, authority) = (fixture.auction, fixture.authority);
+    let vesting = VestingSchedule {
+        start_timestamp: END,
+        cliff_duration: 100,
+        duration: 1000,
+    };
+    fixture
+        .process_as_authority(client::update_vesting(&auction, &authority, Some(vesting)))
+        .unwrap();
+    fixture.deposit(DEPOSIT);
+    let buyer = fixture.add_buyer();
+    let claim = |fixture: &mut Fixture| {
+        let instruction = client::claim(
+            &auction,
+            &fixture.state(),
+            &buyer.wallet,
+            &buyer.sale_account,
+        );
+        fixture.test.process(&[instruction], &[&buyer.wallet])
+    };
+
+    fixture.test.set_timestamp(600);
+    fixture.purchase(&buyer, 100).unwrap();
+    assert_eq!(fixture.test.token_balance(&buyer.sale_account), 0);
+    assert_eq!(
+        fixture.test.token_balance(&fixture.state().auction_pool),
+        DEPOSIT
+    );
+
+    fixture.test.set_timestamp(END + 50);
+    assert_eq!(
+        claim(&mut fixture),
+        Err(auction_error(AuctionError::NothingToClaim))
+    );
+    fixture.test.set_timestamp(END + 500);
+    claim(&mut fixture).unwrap();
+    assert_eq!(fixture.test.token_balance(&buyer.sale_account), 50);
+    assert_eq!(fixture.receipt(&buyer).claimed_amount, 50);
+
+    // the unclaimed purchases stay in the pool
+    assert_eq!(
+        fixture.close(),
+        Err(auction_error(AuctionError::SaleTokensUnclaimed))
+    );
+    fixture.withdraw_unsold().unwrap();
+    assert_eq!(fixture.test.token_balance(&fixture.sale_account), 900);
+
+    fixture.test.set_timestamp(END + 1000);
+    claim(&mut fixture).unwrap();
+    assert_eq!(fixture.test.token_balance(&buyer.sale_account), 100);
+    assert_eq!(
+        claim(&mut fixture),
+        Err(auction_error(AuctionError::NothingToClaim))
+    );
+    fixture.close().unwrap();
+}
+
+#[test]
+fn migrate_auction_rewrites_legacy_auctions() {
+    let mut fixture = Fixture::new(Schedule::default());
+    let authority = fixture.authority;
+    let legacy = LegacyAuction {
+        authority,
+        start_timestamp: START,
+        end_timestamp: START + 100_000,
+        payment_mint: fixture.payment_mint,
+        payment_destination: fixture.payment_destination,
+        sale_mint: fixture.sale_mint,
+        auction_pool: Pubkey::new_unique(),
+        ceil_price: 1000,
+        floor_price: 10,
+        price_hold_duration: 0,
+        auction_authority_bump: 255,
+        auction_pool_bump: 255,
+        last_purchase_timestamp: None,
+        last_purchase_price: 1000,
+    };
+    let mut data = Auction::discriminator().to_vec();
+    legacy.serialize(&mut data).unwrap();
+    data.resize(LegacyAuction::LEN, 0);
+    let auction = Pubkey::new_unique();
+    fixture.test.set_account(
+        &auction,
+        TestAccount {
+            lamports: Rent::default().minimum_balance(LegacyAuction::LEN),
+            data,
+            owner: descending_auction_program::ID,
+            executable: false,
+        },
+    );
+
+    let stranger = fixture.test.add_wallet();
+    assert_eq!(
+        fixture.test.process(
+            &[client::migrate_auction(&auction, &stranger)],
+            &[&stranger]
+        ),
+        Err(auction_error(AuctionError::InvalidAuctionAuthority))
+    );
+    fixture
+        .process_as_authority(client::migrate_auction(&auction, &authority))
+        .unwrap();
+    let account = fixture.test.account(&auction).unwrap();
+    assert_eq!(account.data.len(), Auction::LEN);
+    assert_eq!(
+        account.lamports,
+        Rent::default().minimum_balance(Auction::LEN)
+    );
+
+    let migrated: Auction = fixture.test.get(&auction);
+    assert_eq!(migrated.end_timestamp, Some(START + 100_000));
+    assert_eq!(
+        migrated.decay_curve,
+        DecayCurve::Exponential {
+            half_life: LEGACY_HALF_LIFE
+        }
+    );
+    assert_eq!(migrated.version, AUCTION_VERSION);
+    // the legacy price decays by 1.00005 a second: 1000 / 1.00005^13863 = 500.0001
+    let price = migrated.get_price_at(START + LEGACY_HALF_LIFE).unwrap();
+    assert_eq!(
+        price.to_u64(bonding_math::decimal::Rounding::Down),
+        Some(500)
+    );
+
+    assert_eq!(
+        fixture.process_as_authority(client::migrate_auction(&auction, &authority)),
+        Err(auction_error(AuctionError::InvalidAuctionLayout))
+    );
+}
+
+#[test]
+fn quote_purchase_returns_the_payment() {
+    let mut fixture = Fixture::new(Schedule::default());
+    fixture.deposit(DEPOSIT);
+    let (auction, sale_mint) = (fixture.auction, fixture.sale_mint);
+    fixture.test.set_timestamp(600);
+
+    fixture
+        .test
+        .process(&[client::quote_purchase(&auction, &sale_mint, 10)], &[])
+        .unwrap();
+    assert_eq!(
+        fixture.test.return_data(),
+        Some((
+            descending_auction_program::ID,
+            550u64.to_le_bytes().to_vec()
+        ))
+    );
+
+    fixture.test.set_timestamp(END);
+    assert_eq!(
+        fixture
+            .test
+            .process(&[client::quote_purchase(&auction, &sale_mint, 10)], &[]),
+        Err(auction_error(AuctionError::AuctionNotInProgress))
+    );
+}
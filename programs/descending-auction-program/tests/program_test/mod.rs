@@ -0,0 +1,442 @@
+//! In-process runtime for the program tests
+//!
+//! Runs the auction program's `entry` against accounts held in memory. Its cross-program invocations
+//! go to the token program's processor, or to the part of the system program the accounts constraints
+//! use; the signatures of PDAs are checked against the seeds of the invoking program.
+
+#![allow(dead_code)]
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::sync::Once;
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::{
+    bpf_loader,
+    entrypoint::{ProgramResult, MAX_PERMITTED_DATA_INCREASE, SUCCESS},
+    instruction::Instruction,
+    program_pack::Pack,
+    program_stubs::{set_syscall_stubs, SyscallStubs},
+    program_utils::limited_deserialize,
+    system_instruction::SystemInstruction,
+    system_program, sysvar,
+};
+use anchor_lang::AccountDeserialize;
+use anchor_spl::token::spl_token;
+
+pub const LAMPORTS_PER_WALLET: u64 = 10_000_000_000;
+
+thread_local! {
+    static CLOCK: RefCell<Clock> = RefCell::new(Clock::default());
+    static PROGRAM_IDS: RefCell<Vec<Pubkey>> = const { RefCell::new(vec![]) };
+    static RETURN_DATA: RefCell<Option<(Pubkey, Vec<u8>)>> = const { RefCell::new(None) };
+}
+
+fn current_program_id() -> Pubkey {
+    PROGRAM_IDS.with(|program_ids| *program_ids.borrow().last().unwrap())
+}
+
+struct Stubs;
+
+impl SyscallStubs for Stubs {
+    fn sol_log(&self, _message: &str) {}
+
+    fn sol_log_data(&self, _fields: &[&[u8]]) {}
+
+    fn sol_get_clock_sysvar(&self, var_addr: *mut u8) -> u64 {
+        let clock = CLOCK.with(|clock| clock.borrow().clone());
+        unsafe { *(var_addr as *mut Clock) = clock };
+        SUCCESS
+    }
+
+    fn sol_get_rent_sysvar(&self, var_addr: *mut u8) -> u64 {
+        unsafe { *(var_addr as *mut Rent) = Rent::default() };
+        SUCCESS
+    }
+
+    fn sol_set_return_data(&self, data: &[u8]) {
+        let program_id = current_program_id();
+        RETURN_DATA
+            .with(|return_data| *return_data.borrow_mut() = Some((program_id, data.to_vec())));
+    }
+
+    fn sol_get_return_data(&self) -> Option<(Pubkey, Vec<u8>)> {
+        RETURN_DATA.with(|return_data| return_data.borrow().clone())
+    }
+
+    fn sol_invoke_signed(
+        &self,
+        instruction: &Instruction,
+        account_infos: &[AccountInfo],
+        signers_seeds: &[&[&[u8]]],
+    ) -> ProgramResult {
+        let caller = current_program_id();
+        let pda_signers = signers_seeds
+            .iter()
+            .map(|seeds| Pubkey::create_program_address(seeds, &caller))
+            .collect::<std::result::Result<Vec<_>, _>>()
+            .map_err(|_| ProgramError::InvalidSeeds)?;
+
+        let mut accounts = Vec::with_capacity(instruction.accounts.len());
+        for meta in &instruction.accounts {
+            let account = account_infos
+                .iter()
+                .find(|account| *account.key == meta.pubkey)
+                .ok_or(ProgramError::NotEnoughAccountKeys)?;
+            if meta.is_signer && !account.is_signer && !pda_signers.contains(&meta.pubkey) {
+                return Err(ProgramError::MissingRequiredSignature);
+            }
+            if meta.is_writable && !account.is_writable {
+                return Err(ProgramError::InvalidArgument);
+            }
+            let mut account = account.clone();
+            account.is_signer = meta.is_signer;
+            account.is_writable = meta.is_writable;
+            accounts.push(account);
+        }
+
+        invoke_program(&instruction.program_id, &accounts, &instruction.data)
+    }
+}
+
+fn invoke_program(program_id: &Pubkey, accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+    PROGRAM_IDS.with(|program_ids| program_ids.borrow_mut().push(*program_id));
+    let result = if *program_id == descending_auction_program::ID {
+        descending_auction_program::entry(program_id, accounts, data)
+    } else if *program_id == spl_token::ID {
+        spl_token::processor::Processor::process(program_id, accounts, data)
+    } else if *program_id == system_program::ID {
+        process_system_instruction(accounts, data)
+    } else {
+        Err(ProgramError::IncorrectProgramId)
+    };
+    PROGRAM_IDS.with(|program_ids| program_ids.borrow_mut().pop());
+    result
+}
+
+fn process_system_instruction(accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+    let instruction =
+        limited_deserialize(data, 1232).map_err(|_| ProgramError::InvalidInstructionData)?;
+    match instruction {
+        SystemInstruction::CreateAccount {
+            lamports,
+            space,
+            owner,
+        } => {
+            let (from, to) = (&accounts[0], &accounts[1]);
+            if !to.is_signer {
+                return Err(ProgramError::MissingRequiredSignature);
+            }
+            if to.lamports() > 0 || !to.data_is_empty() || *to.owner != system_program::ID {
+                return Err(ProgramError::AccountAlreadyInitialized);
+            }
+            transfer_lamports(from, to, lamports)?;
+            resize(to, space as usize);
+            to.assign(&owner);
+        }
+        SystemInstruction::Transfer { lamports } => {
+            transfer_lamports(&accounts[0], &accounts[1], lamports)?
+        }
+        SystemInstruction::Allocate { space } => {
+            if !accounts[0].is_signer {
+                return Err(ProgramError::MissingRequiredSignature);
+            }
+            resize(&accounts[0], space as usize);
+        }
+        SystemInstruction::Assign { owner } => {
+            if !accounts[0].is_signer {
+                return Err(ProgramError::MissingRequiredSignature);
+            }
+            accounts[0].assign(&owner);
+        }
+        _ => return Err(ProgramError::InvalidInstructionData),
+    }
+    Ok(())
+}
+
+fn transfer_lamports(from: &AccountInfo, to: &AccountInfo, lamports: u64) -> ProgramResult {
+    if !from.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    let from_lamports = from
+        .lamports()
+        .checked_sub(lamports)
+        .ok_or(ProgramError::InsufficientFunds)?;
+    let to_lamports = to
+        .lamports()
+        .checked_add(lamports)
+        .ok_or(ProgramError::InvalidArgument)?;
+    **from.try_borrow_mut_lamports()? = from_lamports;
+    **to.try_borrow_mut_lamports()? = to_lamports;
+    Ok(())
+}
+
+/// Sets the data length of an account built by `account_info`, zeroing the added bytes
+fn resize(account: &AccountInfo, len: usize) {
+    let mut data = account.data.borrow_mut();
+    let orig_len = data.len();
+    let ptr = data.as_mut_ptr();
+    unsafe {
+        assert!(
+            len as u64 <= *(ptr.offset(-16) as *const u64),
+            "account data exceeds its buffer"
+        );
+        *(ptr.offset(-8) as *mut u64) = len as u64;
+        *data = std::slice::from_raw_parts_mut(ptr, len);
+    }
+    if len > orig_len {
+        data[orig_len..].iter_mut().for_each(|byte| *byte = 0);
+    }
+}
+
+/// Builds an account info over leaked memory laid out as the runtime serializes it: the capacity and
+/// the length of the data precede it, and it can grow by `MAX_PERMITTED_DATA_INCREASE`
+fn account_info(
+    key: &Pubkey,
+    account: &TestAccount,
+    is_signer: bool,
+    is_writable: bool,
+) -> AccountInfo<'static> {
+    let len = account.data.len();
+    let capacity = len + MAX_PERMITTED_DATA_INCREASE;
+    // u64 words keep the length 8-byte aligned, as `AccountInfo::realloc` writes it in place
+    let words = Box::leak(vec![0u64; 2 + capacity.div_ceil(8)].into_boxed_slice());
+    words[0] = capacity as u64;
+    words[1] = len as u64;
+    let buffer =
+        unsafe { std::slice::from_raw_parts_mut(words.as_mut_ptr().add(2) as *mut u8, capacity) };
+    buffer[..len].copy_from_slice(&account.data);
+
+    AccountInfo::new(
+        Box::leak(Box::new(*key)),
+        is_signer,
+        is_writable,
+        Box::leak(Box::new(account.lamports)),
+        &mut buffer[..len],
+        Box::leak(Box::new(account.owner)),
+        account.executable,
+        0,
+    )
+}
+
+#[derive(Clone, Debug)]
+pub struct TestAccount {
+    pub lamports: u64,
+    pub data: Vec<u8>,
+    pub owner: Pubkey,
+    pub executable: bool,
+}
+
+impl Default for TestAccount {
+    fn default() -> Self {
+        Self {
+            lamports: 0,
+            data: vec![],
+            owner: system_program::ID,
+            executable: false,
+        }
+    }
+}
+
+/// Accounts of a test and the clock the programs read
+pub struct ProgramTest {
+    accounts: HashMap<Pubkey, TestAccount>,
+}
+
+impl ProgramTest {
+    pub fn new() -> Self {
+        static STUBS: Once = Once::new();
+        STUBS.call_once(|| {
+            set_syscall_stubs(Box::new(Stubs));
+        });
+
+        let mut test = Self {
+            accounts: HashMap::new(),
+        };
+        for program_id in [
+            descending_auction_program::ID,
+            spl_token::ID,
+            system_program::ID,
+        ]
+        .iter()
+        {
+            test.set_account(
+                program_id,
+                TestAccount {
+                    lamports: 1,
+                    data: vec![],
+                    owner: bpf_loader::ID,
+                    executable: true,
+                },
+            );
+        }
+
+        let mut rent = TestAccount {
+            lamports: 1,
+            data: vec![0; Rent::size_of()],
+            owner: sysvar::ID,
+            executable: false,
+        };
+        let mut rent_info = account_info(&sysvar::rent::ID, &rent, false, false);
+        Rent::default().to_account_info(&mut rent_info).unwrap();
+        rent.data = rent_info.data.borrow().to_vec();
+        test.set_account(&sysvar::rent::ID, rent);
+
+        test.set_timestamp(0);
+        test
+    }
+
+    pub fn set_timestamp(&mut self, unix_timestamp: i64) {
+        CLOCK.with(|clock| clock.borrow_mut().unix_timestamp = unix_timestamp);
+    }
+
+    pub fn set_account(&mut self, key: &Pubkey, account: TestAccount) {
+        self.accounts.insert(*key, account);
+    }
+
+    pub fn account(&self, key: &Pubkey) -> Option<&TestAccount> {
+        self.accounts.get(key)
+    }
+
+    /// A system account funded to pay for the accounts it creates
+    pub fn add_wallet(&mut self) -> Pubkey {
+        let wallet = Pubkey::new_unique();
+        self.set_account(
+            &wallet,
+            TestAccount {
+                lamports: LAMPORTS_PER_WALLET,
+                ..TestAccount::default()
+            },
+        );
+        wallet
+    }
+
+    pub fn create_mint(&mut self, decimals: u8) -> Pubkey {
+        let mint = Pubkey::new_unique();
+        let state = spl_token::state::Mint {
+            mint_authority: Some(Pubkey::new_unique()).into(),
+            decimals,
+            is_initialized: true,
+            ..Default::default()
+        };
+        self.set_packed(&mint, state);
+        mint
+    }
+
+    pub fn create_token_account(&mut self, mint: &Pubkey, owner: &Pubkey, amount: u64) -> Pubkey {
+        let token_account = Pubkey::new_unique();
+        let state = spl_token::state::Account {
+            mint: *mint,
+            owner: *owner,
+            amount,
+            state: spl_token::state::AccountState::Initialized,
+            ..Default::default()
+        };
+        self.set_packed(&token_account, state);
+        token_account
+    }
+
+    fn set_packed<T: Pack>(&mut self, key: &Pubkey, state: T) {
+        let mut data = vec![0; T::LEN];
+        T::pack(state, &mut data).unwrap();
+        self.set_account(
+            key,
+            TestAccount {
+                lamports: Rent::default().minimum_balance(T::LEN),
+                data,
+                owner: spl_token::ID,
+                executable: false,
+            },
+        );
+    }
+
+    pub fn token_balance(&self, token_account: &Pubkey) -> u64 {
+        let account = self.account(token_account).expect("token account");
+        spl_token::state::Account::unpack(&account.data)
+            .unwrap()
+            .amount
+    }
+
+    pub fn lamports(&self, key: &Pubkey) -> u64 {
+        self.account(key).map_or(0, |account| account.lamports)
+    }
+
+    pub fn get<T: AccountDeserialize>(&self, key: &Pubkey) -> T {
+        let account = self.account(key).expect("account");
+        T::try_deserialize(&mut account.data.as_slice()).unwrap()
+    }
+
+    /// Return data of the last processed transaction
+    pub fn return_data(&self) -> Option<(Pubkey, Vec<u8>)> {
+        RETURN_DATA.with(|return_data| return_data.borrow().clone())
+    }
+
+    /// Processes the instructions of a transaction signed by `signers`; none of its changes are kept
+    /// when one of them fails
+    pub fn process(&mut self, instructions: &[Instruction], signers: &[&Pubkey]) -> ProgramResult {
+        RETURN_DATA.with(|return_data| *return_data.borrow_mut() = None);
+        let mut accounts = self.accounts.clone();
+        for instruction in instructions {
+            process_instruction(&mut accounts, instruction, signers)?;
+        }
+        self.accounts = accounts;
+        Ok(())
+    }
+}
+
+fn process_instruction(
+    accounts: &mut HashMap<Pubkey, TestAccount>,
+    instruction: &Instruction,
+    signers: &[&Pubkey],
+) -> ProgramResult {
+    let mut keys: Vec<Pubkey> = vec![];
+    for meta in &instruction.accounts {
+        if meta.is_signer && !signers.contains(&&meta.pubkey) {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+        if !keys.contains(&meta.pubkey) {
+            keys.push(meta.pubkey);
+        }
+    }
+
+    // an account listed several times is shared, as in the runtime
+    let account_infos: Vec<AccountInfo> = keys
+        .iter()
+        .map(|key| {
+            let metas = instruction
+                .accounts
+                .iter()
+                .filter(|meta| meta.pubkey == *key);
+            let is_signer = metas.clone().any(|meta| meta.is_signer);
+            let is_writable = metas.clone().any(|meta| meta.is_writable);
+            let account = accounts.get(key).cloned().unwrap_or_default();
+            account_info(key, &account, is_signer, is_writable)
+        })
+        .collect();
+    let instruction_accounts: Vec<AccountInfo> = instruction
+        .accounts
+        .iter()
+        .map(|meta| account_infos[keys.iter().position(|key| *key == meta.pubkey).unwrap()].clone())
+        .collect();
+
+    invoke_program(
+        &instruction.program_id,
+        &instruction_accounts,
+        &instruction.data,
+    )?;
+
+    for account_info in &account_infos {
+        if account_info.lamports() == 0 {
+            accounts.remove(account_info.key);
+        } else {
+            let account = TestAccount {
+                lamports: account_info.lamports(),
+                data: account_info.data.borrow().to_vec(),
+                owner: *account_info.owner,
+                executable: account_info.executable,
+            };
+            accounts.insert(*account_info.key, account);
+        }
+    }
+    Ok(())
+}